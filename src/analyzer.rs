@@ -3,3 +3,5 @@ pub mod function_table;
 pub mod coroutine_table;
 pub mod semantic;
 pub mod task_table;
+pub mod constant_folding;
+pub mod tail_call;
@@ -0,0 +1,152 @@
+use crate::{
+    common::{
+        operator::{Arithmetic, BinaryLogical, Comparison, Logical, UnaryLogical},
+        types::LiteralValue,
+    },
+    parser::node::PrivateNode,
+};
+
+/// リテラルのみで構成された式を畳み込む
+/// 
+/// 解析用の機能であり、実行時の評価には影響しない
+/// 
+/// ## Argments
+/// 
+/// - `node` - 畳み込む式
+/// 
+/// ## Return
+/// 
+/// - 畳み込めた場合はその値、変数や関数呼び出しを含む場合は`None`
+/// 
+/// ## Example
+/// 
+/// ```
+/// use green::{analyzer::constant_folding::fold_constant, common::{operator::Arithmetic, types::LiteralValue}, parser::node::PrivateNode};
+/// 
+/// // 1 + 2
+/// let node = PrivateNode::Arithmetic {
+///     operator: Arithmetic::Plus,
+///     left: Box::new(PrivateNode::Literal { value: LiteralValue::Int(1) }),
+///     right: Some(Box::new(PrivateNode::Literal { value: LiteralValue::Int(2) })),
+/// };
+/// assert_eq!(fold_constant(&node), Some(LiteralValue::Int(3)));
+/// ```
+pub fn fold_constant(node: &PrivateNode) -> Option<LiteralValue> {
+    match node {
+        PrivateNode::Literal { value } => Some(value.clone()),
+        PrivateNode::Arithmetic { operator, left, right } => {
+            let left = fold_constant(left)?;
+            match right {
+                Some(right) => fold_arithmetic(operator, left, fold_constant(right)?),
+                None => {
+                    match (operator, left) {
+                        (Arithmetic::Plus, LiteralValue::Int(value)) => Some(LiteralValue::Int(value)),
+                        (Arithmetic::Minus, LiteralValue::Int(value)) => Some(LiteralValue::Int(value.checked_neg()?)),
+                        (Arithmetic::Plus, LiteralValue::Float(value)) => Some(LiteralValue::Float(value)),
+                        (Arithmetic::Minus, LiteralValue::Float(value)) => Some(LiteralValue::Float(-value)),
+                        _ => None,
+                    }
+                },
+            }
+        },
+        PrivateNode::Conditional { condition, then_value, else_value } => {
+            match fold_constant(condition)? {
+                LiteralValue::Bool(true) => fold_constant(then_value),
+                LiteralValue::Bool(false) => fold_constant(else_value),
+                _ => None,
+            }
+        },
+        PrivateNode::Compare { operator, left, right } => {
+            let left = fold_constant(left)?;
+            let right = fold_constant(right)?;
+            fold_compare(operator, left, right)
+        },
+        PrivateNode::Logical { operator, left, right } => {
+            let left = match fold_constant(left)? {
+                LiteralValue::Bool(value) => value,
+                _ => return None,
+            };
+            match operator {
+                Logical::Unary(UnaryLogical::Not) => Some(LiteralValue::Bool(!left)),
+                Logical::Binary(binary_operator) => {
+                    let right = match fold_constant(right.as_ref()?)? {
+                        LiteralValue::Bool(value) => value,
+                        _ => return None,
+                    };
+                    let result = match binary_operator {
+                        BinaryLogical::Or => left || right,
+                        BinaryLogical::And => left && right,
+                        BinaryLogical::Xor => left != right,
+                    };
+                    Some(LiteralValue::Bool(result))
+                },
+            }
+        },
+        _ => None,
+    }
+}
+
+/// 四則演算の畳み込み
+fn fold_arithmetic(operator: &Arithmetic, left: LiteralValue, right: LiteralValue) -> Option<LiteralValue> {
+    if *operator == Arithmetic::Concat {
+        return match (&left, &right) {
+            (LiteralValue::Int(_) | LiteralValue::Float(_) | LiteralValue::String(_),
+             LiteralValue::Int(_) | LiteralValue::Float(_) | LiteralValue::String(_)) => {
+                Some(LiteralValue::String(left.to_string() + &right.to_string()))
+            },
+            _ => None,
+        }
+    }
+    match (left, right) {
+        (LiteralValue::Int(left), LiteralValue::Int(right)) => {
+            let result = match operator {
+                Arithmetic::Plus => left.checked_add(right)?,
+                Arithmetic::Minus => left.checked_sub(right)?,
+                Arithmetic::Multiply => left.checked_mul(right)?,
+                Arithmetic::Divide => left.checked_div(right)?,
+                Arithmetic::Concat => unreachable!(),
+            };
+            Some(LiteralValue::Int(result))
+        },
+        (LiteralValue::Int(left), LiteralValue::Float(right)) => fold_float_arithmetic(operator, left as f64, right),
+        (LiteralValue::Float(left), LiteralValue::Int(right)) => fold_float_arithmetic(operator, left, right as f64),
+        (LiteralValue::Float(left), LiteralValue::Float(right)) => fold_float_arithmetic(operator, left, right),
+        _ => None,
+    }
+}
+
+fn fold_float_arithmetic(operator: &Arithmetic, left: f64, right: f64) -> Option<LiteralValue> {
+    let result = match operator {
+        Arithmetic::Plus => left + right,
+        Arithmetic::Minus => left - right,
+        Arithmetic::Multiply => left * right,
+        Arithmetic::Divide => left / right,
+        Arithmetic::Concat => unreachable!(),
+    };
+    Some(LiteralValue::Float(result))
+}
+
+/// 比較演算の畳み込み
+fn fold_compare(operator: &Comparison, left: LiteralValue, right: LiteralValue) -> Option<LiteralValue> {
+    let (left, right) = match (left, right) {
+        (LiteralValue::Int(left), LiteralValue::Int(right)) => (left as f64, right as f64),
+        (LiteralValue::Int(left), LiteralValue::Float(right)) => (left as f64, right),
+        (LiteralValue::Float(left), LiteralValue::Int(right)) => (left, right as f64),
+        (LiteralValue::Float(left), LiteralValue::Float(right)) => (left, right),
+        (LiteralValue::String(left), LiteralValue::String(right)) => return Some(LiteralValue::Bool(compare(operator, left, right))),
+        _ => return None,
+    };
+    Some(LiteralValue::Bool(compare(operator, left, right)))
+}
+
+/// 比較演算子の適用（文字列は辞書順）
+fn compare<T: PartialOrd>(operator: &Comparison, left: T, right: T) -> bool {
+    match operator {
+        Comparison::Equal => left == right,
+        Comparison::NotEqual => left != right,
+        Comparison::GreaterEqual => left >= right,
+        Comparison::Greater => left > right,
+        Comparison::LessEqual => left <= right,
+        Comparison::Less => left < right,
+    }
+}
@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use crate::parser::node::BlockNode;
+use crate::{common::types::Type, parser::node::BlockNode};
 
 use super::variable_table::VariableScope;
 
@@ -17,6 +17,9 @@ pub struct CoroutineInfo {
     // /// 戻り値
     // pub return_type: Option<Type>,
 
+    /// yieldする値の型
+    pub yield_type: Option<Type>,
+
     /// コルーチン内の変数
     pub local_variables: VariableScope,
 
@@ -35,7 +38,7 @@ impl CoroutineTable {
         }
     }
 
-    pub fn coroutine_definition(&mut self, name: &str, doc: Option<&str>, process: &BlockNode) {
+    pub fn coroutine_definition(&mut self, name: &str, doc: Option<&str>, yield_type: &Option<Type>, process: &BlockNode) {
         let doc = match doc {
             Some(doc) => doc,
             None => "",
@@ -44,6 +47,7 @@ impl CoroutineTable {
         let coroutine_info = CoroutineInfo {
             name: name.to_string(),
             doc: doc.to_string(),
+            yield_type: yield_type.clone(),
             local_variables: VariableScope::new(None),
             process: process.clone(),
         };
@@ -57,4 +61,15 @@ impl CoroutineTable {
     pub fn get_coroutine_info_mut(&mut self, name: &str) -> Option<&mut CoroutineInfo> {
         self.table.get_mut(name)
     }
+
+    /// 登録されている全てのコルーチン
+    pub fn coroutines(&self) -> Vec<&CoroutineInfo> {
+        self.table.values().collect()
+    }
+
+    /// 名前空間`namespace`に属する定義が存在するか
+    pub fn has_namespace(&self, namespace: &str) -> bool {
+        let prefix = format!("{}.", namespace);
+        self.table.keys().any(|name| name.starts_with(&prefix))
+    }
 }
\ No newline at end of file
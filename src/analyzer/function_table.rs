@@ -1,6 +1,9 @@
 use std::collections::HashMap;
 use crate::{common::types::{BlockType, Type}, parser::node::{BlockNode, ParameterNode}};
-use super::variable_table::VariableScope;
+use super::{tail_call::TailCall, variable_table::VariableScope};
+
+/// 組み込み関数名の一覧
+pub const BUILTIN_FUNCTIONS: &[&str] = &["print", "println", "print_raw", "input", "debug", "to_string", "to_int", "to_float", "abs", "sqrt", "pow", "char_at", "substring", "step", "typeof", "assert", "assert_eq", "format", "chars", "from_chars"];
 
 #[derive(Debug, Clone)]
 pub struct FunctionInfo {
@@ -20,11 +23,60 @@ pub struct FunctionInfo {
     pub local_variables: VariableScope,
 
     /// 可変長引数であるか  
-    /// trueであれば、引数の数と型をチェックしない
+    /// パラメータのない組み込み関数は、引数の数と型をチェックしない  
+    /// ユーザー定義関数は最後のパラメータ（要素型の配列）に、余った引数をまとめて渡す
     pub is_variadic: bool,
 
     /// 関数の処理
     pub process: BlockNode,
+
+    /// 引数ごとに戻り値をキャッシュするか（`@memoize`）
+    pub memoize: bool,
+
+    /// 末尾位置の関数呼び出し（意味解析で設定する）
+    pub tail_calls: Vec<TailCall>,
+}
+
+impl FunctionInfo {
+    /// 関数のシグネチャ（`name(a: int, b: float) -> int`）
+    pub fn signature(&self) -> String {
+        let parameters = self.parameters.iter().enumerate()
+            .map(|(index, param)| match (&param.variable_type, index >= self.fixed_parameter_count()) {
+                (Type::Array(element_type), true) => format!("{}: {}...", param.name, element_type.to_string()),
+                _ => format!("{}: {}", param.name, param.variable_type.to_string()),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        match &self.return_type {
+            Some(return_type) => format!("{}({}) -> {}", self.name, parameters, return_type.to_string()),
+            None => format!("{}({})", self.name, parameters),
+        }
+    }
+
+    /// 可変長引数を除いたパラメータの数
+    pub fn fixed_parameter_count(&self) -> usize {
+        if self.is_variadic {
+            self.parameters.len().saturating_sub(1)
+        } else {
+            self.parameters.len()
+        }
+    }
+
+    /// 引数の位置に対応するパラメータと、引数に期待する型
+    /// 
+    /// 可変長引数に渡す引数は、配列の要素型を期待する
+    pub fn parameter_at(&self, index: usize) -> Option<(&ParameterNode, Type)> {
+        if index < self.fixed_parameter_count() {
+            let param = &self.parameters[index];
+            return Some((param, param.variable_type.clone()))
+        }
+        match self.parameters.last() {
+            Some(param @ ParameterNode { variable_type: Type::Array(element_type), .. }) if self.is_variadic => {
+                Some((param, *element_type.clone()))
+            },
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -44,6 +96,174 @@ impl FunctionTable {
             true,
             &BlockNode{block_type: BlockType::Function, statements: vec![]},
         );
+        // printlnはprintの別名
+        table.function_definition(
+            "println",
+            None,
+            &vec![],
+            &None,
+            true,
+            &BlockNode{block_type: BlockType::Function, statements: vec![]},
+        );
+        table.function_definition(
+            "print_raw",
+            None,
+            &vec![],
+            &None,
+            true,
+            &BlockNode{block_type: BlockType::Function, statements: vec![]},
+        );
+        table.function_definition(
+            "input",
+            None,
+            &vec![],
+            &Some(Type::String),
+            false,
+            &BlockNode{block_type: BlockType::Function, statements: vec![]},
+        );
+        table.function_definition(
+            "debug",
+            None,
+            &vec![],
+            &None,
+            true,
+            &BlockNode{block_type: BlockType::Function, statements: vec![]},
+        );
+        table.function_definition(
+            "to_string",
+            None,
+            &vec![],
+            &Some(Type::String),
+            true,
+            &BlockNode{block_type: BlockType::Function, statements: vec![]},
+        );
+        table.function_definition(
+            "to_int",
+            None,
+            &vec![],
+            &Some(Type::Int),
+            true,
+            &BlockNode{block_type: BlockType::Function, statements: vec![]},
+        );
+        table.function_definition(
+            "to_float",
+            None,
+            &vec![],
+            &Some(Type::Float),
+            true,
+            &BlockNode{block_type: BlockType::Function, statements: vec![]},
+        );
+        // absは引数の型（intまたはfloat）をそのまま返すため、意味解析で個別に検査する
+        table.function_definition(
+            "abs",
+            None,
+            &vec![],
+            &None,
+            true,
+            &BlockNode{block_type: BlockType::Function, statements: vec![]},
+        );
+        table.function_definition(
+            "sqrt",
+            None,
+            &vec![ParameterNode { name: "x".to_string(), variable_type: Type::Float }],
+            &Some(Type::Float),
+            false,
+            &BlockNode{block_type: BlockType::Function, statements: vec![]},
+        );
+        table.function_definition(
+            "pow",
+            None,
+            &vec![
+                ParameterNode { name: "base".to_string(), variable_type: Type::Float },
+                ParameterNode { name: "exp".to_string(), variable_type: Type::Float },
+            ],
+            &Some(Type::Float),
+            false,
+            &BlockNode{block_type: BlockType::Function, statements: vec![]},
+        );
+        table.function_definition(
+            "char_at",
+            None,
+            &vec![
+                ParameterNode { name: "s".to_string(), variable_type: Type::String },
+                ParameterNode { name: "i".to_string(), variable_type: Type::Int },
+            ],
+            &Some(Type::String),
+            false,
+            &BlockNode{block_type: BlockType::Function, statements: vec![]},
+        );
+        table.function_definition(
+            "substring",
+            None,
+            &vec![
+                ParameterNode { name: "s".to_string(), variable_type: Type::String },
+                ParameterNode { name: "start".to_string(), variable_type: Type::Int },
+                ParameterNode { name: "end".to_string(), variable_type: Type::Int },
+            ],
+            &Some(Type::String),
+            false,
+            &BlockNode{block_type: BlockType::Function, statements: vec![]},
+        );
+        table.function_definition(
+            "chars",
+            None,
+            &vec![ParameterNode { name: "s".to_string(), variable_type: Type::String }],
+            &Some(Type::Array(Box::new(Type::String))),
+            false,
+            &BlockNode{block_type: BlockType::Function, statements: vec![]},
+        );
+        table.function_definition(
+            "from_chars",
+            None,
+            &vec![ParameterNode { name: "arr".to_string(), variable_type: Type::Array(Box::new(Type::String)) }],
+            &Some(Type::String),
+            false,
+            &BlockNode{block_type: BlockType::Function, statements: vec![]},
+        );
+        // stepの引数はタスク名のため、意味解析で個別に検査する
+        table.function_definition(
+            "step",
+            None,
+            &vec![],
+            &Some(Type::String),
+            true,
+            &BlockNode{block_type: BlockType::Function, statements: vec![]},
+        );
+        // typeofは任意の型の引数を1つ受け取るため、意味解析で個別に検査する
+        table.function_definition(
+            "typeof",
+            None,
+            &vec![],
+            &Some(Type::String),
+            true,
+            &BlockNode{block_type: BlockType::Function, statements: vec![]},
+        );
+        table.function_definition(
+            "assert",
+            None,
+            &vec![ParameterNode { name: "cond".to_string(), variable_type: Type::Bool }],
+            &None,
+            false,
+            &BlockNode{block_type: BlockType::Function, statements: vec![]},
+        );
+        // assert_eqは同じ型の任意の値を2つ受け取るため、意味解析で個別に検査する
+        table.function_definition(
+            "assert_eq",
+            None,
+            &vec![],
+            &None,
+            true,
+            &BlockNode{block_type: BlockType::Function, statements: vec![]},
+        );
+        // formatはテンプレートに続けて任意の型の値を受け取るため、意味解析で個別に検査する
+        table.function_definition(
+            "format",
+            None,
+            &vec![],
+            &Some(Type::String),
+            true,
+            &BlockNode{block_type: BlockType::Function, statements: vec![]},
+        );
         return table
     }
 
@@ -53,14 +273,21 @@ impl FunctionTable {
             None => "",
         };
 
+        let mut local_variables = VariableScope::new(None);
+        for parameter in parameters {
+            local_variables.variable_declare(&parameter.name, &parameter.variable_type);
+        }
+
         let function_info = FunctionInfo {
             name: name.to_string(),
             doc: doc.to_string(),
             parameters: parameters.clone(),
             return_type: return_type.clone(),
-            local_variables: VariableScope::new(None),
+            local_variables,
             is_variadic,
             process: block.clone(),
+            memoize: false,
+            tail_calls: Vec::new(),
         };
         self.table.insert(name.to_string(), function_info);
     }
@@ -72,4 +299,10 @@ impl FunctionTable {
     pub fn get_function_info_mut(&mut self, name: &str) -> Option<&mut FunctionInfo> {
         self.table.get_mut(name)
     }
+
+    /// 名前空間`namespace`に属する定義が存在するか
+    pub fn has_namespace(&self, namespace: &str) -> bool {
+        let prefix = format!("{}.", namespace);
+        self.table.keys().any(|name| name.starts_with(&prefix))
+    }
 }
\ No newline at end of file
@@ -1,6 +1,7 @@
-use crate::{common::types::{LiteralValue, Type}, error::{error_code::ErrorCode, error_context::ErrorContext}, parser::node::*};
+use std::collections::HashSet;
+use crate::{common::{operator::Arithmetic, types::{LiteralValue, Type}}, error::{error_code::ErrorCode, error_context::ErrorContext}, parser::{node::*, normalize::blocks_structurally_equal}};
 
-use super::{coroutine_table::CoroutineTable, function_table::FunctionTable, task_table::TaskTable, variable_table::VariableTable};
+use super::{coroutine_table::CoroutineTable, function_table::{FunctionTable, BUILTIN_FUNCTIONS}, tail_call::find_tail_calls, task_table::TaskTable, variable_table::{VariableScope, VariableTable}};
 
 #[derive(Debug, Clone)]
 pub struct Semantic {
@@ -8,9 +9,17 @@ pub struct Semantic {
     pub variable_table: VariableTable,
     pub coroutine_table: CoroutineTable,
     pub task_table: TaskTable,
+    /// 実行を妨げない診断（未使用の変数など）
+    pub warnings: Vec<ErrorContext>,
     
     errors: Vec<ErrorContext>,
     analysis_name: String,
+    /// 解析中の地点で初期化済みの変数
+    initialized_variables: HashSet<String>,
+    /// 解析中の関数、コルーチンで宣言された変数と宣言位置
+    declared_variables: Vec<(String, u32, u32)>,
+    /// 解析中の関数、コルーチンで値を参照された変数
+    used_variables: HashSet<String>,
 }
 impl Semantic {
     fn new() -> Self {
@@ -19,45 +28,262 @@ impl Semantic {
             variable_table: VariableTable::new(),
             coroutine_table: CoroutineTable::new(),
             task_table: TaskTable::new(),
+            warnings: Vec::new(),
             errors: Vec::new(),
 
             analysis_name: "".to_string(),
+            initialized_variables: HashSet::new(),
+            declared_variables: Vec::new(),
+            used_variables: HashSet::new(),
         }
     }
 
+    /// 意味解析のエラー
+    pub fn errors(&self) -> &Vec<ErrorContext> {
+        &self.errors
+    }
+
+    /// 実行を開始する関数が定義されているか検査する
+    /// 
+    /// 実行しない解析（JSON出力など）では呼び出さない
+    pub fn check_entry_point(&mut self, name: &str) {
+        if self.function_table.get_function_info(name).is_none() {
+            self.errors.push(
+                ErrorContext::new(
+                    ErrorCode::Semantic037,
+                    None, None,
+                    vec![("function_name", name)],
+                )
+            );
+        }
+    }
+
+    /// 関数、コルーチンごとの変数スコープを出力用の文字列にする
+    pub fn dump_scopes(&self) -> String {
+        let mut functions = self.function_table.table.values()
+            .filter(|function| !BUILTIN_FUNCTIONS.contains(&function.name.as_str()))
+            .collect::<Vec<_>>();
+        functions.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut coroutines = self.coroutine_table.coroutines();
+        coroutines.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut dump = String::new();
+        for function in functions {
+            dump.push_str(&format!("function {}\n", function.name));
+            dump.push_str(&function.local_variables.dump(0, "  "));
+        }
+        for coroutine in coroutines {
+            dump.push_str(&format!("coroutine {}\n", coroutine.name));
+            dump.push_str(&coroutine.local_variables.dump(0, "  "));
+        }
+        dump
+    }
+
     /// 意味解析処理
     fn semantic(&mut self, ast: &RootNode) {
-        let RootNode { functions, coroutines } = ast.clone();
+        let RootNode { functions, coroutines, imports:_, todos:_ } = ast.clone();
 
-        for FunctionDefinitionNode { name, parameters, return_type, block, doc } in &functions {
-            self.function_table.function_definition(name, doc.as_deref(), parameters, return_type, false, block);
+        // 重複定義された関数（2つ目以降は解析しない）
+        let mut duplicates = HashSet::new();
+        for (index, FunctionDefinitionNode { name, parameters, return_type, block, doc, memoize, is_variadic }) in functions.iter().enumerate() {
+            if BUILTIN_FUNCTIONS.contains(&name.as_str()) {
+                self.errors.push(
+                    ErrorContext::new(
+                        ErrorCode::Semantic034,
+                        None, None,
+                        vec![("function_name", name)],
+                    )
+                );
+                duplicates.insert(index);
+                continue;
+            }
+            // 関数表は名前のみで管理するため、引数の個数が異なっても重複とする
+            if let Some(defined) = self.function_table.get_function_info(name) {
+                self.errors.push(
+                    ErrorContext::new(
+                        ErrorCode::Semantic021,
+                        None, None,
+                        vec![
+                            ("function_name", name),
+                            ("defined", &defined.parameters.len().to_string()),
+                            ("redefined", &parameters.len().to_string()),
+                        ],
+                    )
+                );
+                duplicates.insert(index);
+                continue;
+            }
+            self.check_duplicate_parameters(name, parameters);
+            self.function_table.function_definition(name, doc.as_deref(), parameters, return_type, *is_variadic, block);
+            if *memoize {
+                self.check_memoize(name, parameters, return_type);
+            }
         }
 
-        for CoroutineDefinitionNode { name, block, doc} in &coroutines {
-            self.coroutine_table.coroutine_definition(name, doc.as_deref(), block);
+        // 重複定義されたコルーチン（2つ目以降は解析しない）
+        let mut duplicate_coroutines = HashSet::new();
+        for (index, CoroutineDefinitionNode { name, yield_type, block, doc}) in coroutines.iter().enumerate() {
+            if self.coroutine_table.get_coroutine_info(name).is_some() {
+                self.errors.push(
+                    ErrorContext::new(
+                        ErrorCode::Semantic035,
+                        None, None,
+                        vec![("coroutine_name", name)],
+                    )
+                );
+                duplicate_coroutines.insert(index);
+                continue;
+            }
+            self.coroutine_table.coroutine_definition(name, doc.as_deref(), yield_type, block);
         }
 
-        for FunctionDefinitionNode { name, parameters:_, return_type:_, block, doc:_ } in functions {
+        for (index, FunctionDefinitionNode { name, parameters, return_type, block, doc:_, memoize:_, is_variadic:_ }) in functions.into_iter().enumerate() {
+            if duplicates.contains(&index) {
+                continue;
+            }
             self.analysis_name = name;
+            self.initialized_variables = parameters.iter().map(|param| param.name.clone()).collect();
             self.semantic_block(&block);
+
+            if return_type.is_some() && !Self::always_returns(&block) {
+                self.errors.push(
+                    ErrorContext::new(
+                        ErrorCode::Semantic012,
+                        None, None,
+                        vec![("function_name", &self.analysis_name)],
+                    )
+                );
+            }
+            self.check_unused_variables();
+            if let Some(function_info) = self.function_table.get_function_info_mut(&self.analysis_name) {
+                function_info.tail_calls = find_tail_calls(&function_info.name, &block);
+            }
             self.analysis_name = "".to_string();
         }
 
-        for CoroutineDefinitionNode { name, block, doc:_ } in coroutines {
+        for (index, CoroutineDefinitionNode { name, yield_type:_, block, doc:_ }) in coroutines.into_iter().enumerate() {
+            if duplicate_coroutines.contains(&index) {
+                continue;
+            }
             self.analysis_name = name;
+            self.initialized_variables = HashSet::new();
             self.semantic_block(&block);
+            self.check_unused_variables();
             self.analysis_name = "".to_string();
         }
     }
 
+    /// 同じ関数の引数名が重複していないか検査する
+    fn check_duplicate_parameters(&mut self, function_name: &str, parameters: &[ParameterNode]) {
+        let mut names = HashSet::new();
+        for param in parameters {
+            if !names.insert(param.name.as_str()) {
+                self.errors.push(
+                    ErrorContext::new(
+                        ErrorCode::Semantic036,
+                        None, None,
+                        vec![
+                            ("function_name", function_name),
+                            ("parameter_name", &param.name),
+                        ],
+                    )
+                );
+            }
+        }
+    }
+
+    /// `@memoize`を指定できる関数か検査し、関数表に設定する
+    /// 
+    /// 戻り値の型があり、引数が全てスカラー型（配列以外）の関数のみ指定できる
+    fn check_memoize(&mut self, name: &str, parameters: &[ParameterNode], return_type: &Option<Type>) {
+        let scalar_parameters = parameters.iter().all(|param| !matches!(param.variable_type, Type::Array(_)));
+        if return_type.is_none() || !scalar_parameters {
+            self.errors.push(
+                ErrorContext::new(
+                    ErrorCode::Semantic030,
+                    None, None,
+                    vec![("function_name", name)],
+                )
+            );
+            return
+        }
+        if let Some(function_info) = self.function_table.get_function_info_mut(name) {
+            function_info.memoize = true;
+        }
+    }
+
+    /// 宣言後に一度も参照されていない変数を警告する
+    /// 
+    /// 引数と、`_`で始まる変数は対象外
+    fn check_unused_variables(&mut self) {
+        for (name, row, col) in std::mem::take(&mut self.declared_variables) {
+            if !name.starts_with('_') && !self.used_variables.contains(&name) {
+                self.warnings.push(
+                    ErrorContext::new(
+                        ErrorCode::Warning001,
+                        Some(row), Some(col),
+                        vec![
+                            ("variable_name", &name),
+                            ("name", &self.analysis_name),
+                        ],
+                    )
+                );
+            }
+        }
+        self.used_variables.clear();
+    }
+
     fn semantic_block(&mut self, block: &BlockNode) {
+        // 同じブロック内で、直前にreturn, break, continueがあるか
+        let mut terminator: Option<&str> = None;
         for statement in block.statements.clone() {
+            if let Some(keyword) = terminator.take() {
+                if !matches!(statement, PrivateNode::ProcessComment { .. }) {
+                    let (row, col) = statement_position(&statement);
+                    self.warnings.push(
+                        ErrorContext::new(
+                            ErrorCode::Warning002,
+                            row, col,
+                            vec![
+                                ("keyword", keyword),
+                                ("name", &self.analysis_name),
+                            ],
+                        )
+                    );
+                } else {
+                    terminator = Some(keyword);
+                }
+            }
+
             self.semantic_statement(&statement);
+
+            match statement {
+                PrivateNode::ReturnStatement { .. } => terminator = Some("return"),
+                PrivateNode::Break { .. } => terminator = Some("break"),
+                PrivateNode::Continue { .. } => terminator = Some("continue"),
+                _ => {},
+            }
+        }
+    }
+
+    /// if文、ループのブロックの意味解析
+    /// 
+    /// ブロック内で宣言された変数は、ブロックの外から参照できない
+    fn semantic_nested_block(&mut self, block: &BlockNode) {
+        if let Some(local_variables) = self.local_variables_mut() {
+            local_variables.enter_block();
+        }
+        self.semantic_block(block);
+        if let Some(local_variables) = self.local_variables_mut() {
+            local_variables.leave_block();
         }
     }
 
     fn semantic_statement(&mut self, statement: &PrivateNode) -> Option<Type> {
         match statement {
+            PrivateNode::Arithmetic { operator: Arithmetic::Concat, left, right: Some(right) } => {
+                return self.semantic_concat(left, right).ok()
+            },
             PrivateNode::Arithmetic { operator, left, right } => {
                 if let Some(right) = right {
                     match self.semantic_binary(&operator.to_string(), &left, &right) {
@@ -80,63 +306,102 @@ impl Semantic {
                     }
                 }
             },
-            PrivateNode::Break => {},
+            PrivateNode::Break { label:_ } => {},
             PrivateNode::Compare { operator, left, right } => {
                 match self.semantic_binary(&operator.to_string(), &left, &right) {
-                    Ok(value_type) => return Some(value_type),
+                    Ok(_) => return Some(Type::Bool),
                     Err(_) => return None,
                 }
             },
-            PrivateNode::Continue => {},
-            PrivateNode::CoroutineInstantiation { task_name, coroutine_name} => {
+            PrivateNode::Continue { label:_ } => {},
+            PrivateNode::CoroutineInstantiation { task_name, coroutine_name, row, col } => {
                 if let Some(coroutine_info) = self.coroutine_table.get_coroutine_info(coroutine_name) {
                     self.task_table.add_task(task_name, coroutine_name, &coroutine_info.process);
                 } else {
+                    let error = self.undefined_call_error("コルーチン", coroutine_name, *row, *col);
+                    self.errors.push(error);
+                }
+            },
+            PrivateNode::CoroutineResume { task_name } => {
+                let task = self.task_table.get_task(task_name)?;
+                return self.coroutine_table.get_coroutine_info(&task.coroutine_name)?.yield_type
+            },
+            PrivateNode::Error => {},
+            PrivateNode::ExpressionStatement { expression } => {
+                self.semantic_statement(expression);
+            },
+            PrivateNode::FunctionCall { name, arguments, return_flg:_, row, col } if name == "abs" => {
+                return self.semantic_abs(arguments, *row, *col)
+            },
+            PrivateNode::FunctionCall { name, arguments, return_flg:_, row, col } if name == "step" => {
+                return self.semantic_step(arguments, *row, *col)
+            },
+            PrivateNode::FunctionCall { name, arguments, return_flg:_, row, col } if name == "typeof" => {
+                return self.semantic_typeof(arguments, *row, *col)
+            },
+            PrivateNode::FunctionCall { name, arguments, return_flg:_, row, col } if name == "format" => {
+                return self.semantic_format(arguments, *row, *col)
+            },
+            PrivateNode::FunctionCall { name, arguments, return_flg, row, col } if name == "assert_eq" => {
+                self.semantic_assert_eq(arguments, *row, *col);
+                if *return_flg {
                     self.errors.push(
                         ErrorContext::new(
-                            ErrorCode::Semantic004,
-                            None, None,
-                            vec![
-                                ("statement", "コルーチン"),
-                                ("name", coroutine_name),
-                            ],
+                            ErrorCode::Semantic005,
+                            Some(*row), Some(*col),
+                            vec![("function_name", name)],
                         )
                     );
                 }
+                return None
             },
-            PrivateNode::CoroutineResume { task_name } => {
-                self.task_table.get_task(task_name);
-            },
-            PrivateNode::Error => {},
-            PrivateNode::FunctionCall { name, arguments, return_flg } => {
+            PrivateNode::FunctionCall { name, arguments, return_flg, row, col } => {
                 match self.function_table.get_function_info(&name) {
                     Some(function_info) => {
-                        if !function_info.is_variadic && function_info.parameters.len() != arguments.len() {
+                        // 可変長引数の関数は、固定のパラメータの数以上の引数を受け取る
+                        let fixed_count = function_info.fixed_parameter_count();
+                        let arity_mismatch = if function_info.is_variadic {
+                            arguments.len() < fixed_count
+                        } else {
+                            arguments.len() != fixed_count
+                        };
+                        if arity_mismatch {
+                            let expected = if function_info.is_variadic {
+                                format!("{}以上", fixed_count)
+                            } else {
+                                fixed_count.to_string()
+                            };
                             self.errors.push(
                                 ErrorContext::new(
                                     ErrorCode::Semantic008,
-                                    None, None, 
+                                    Some(*row), Some(*col), 
                                     vec![
-                                        ("parameter", &function_info.parameters.len().to_string()),
+                                        ("parameter", &expected),
                                         ("argument", &arguments.len().to_string()),
                                         ("name", name),
+                                        ("signature", &function_info.signature()),
                                     ],
                                 )
                             );
                             return None
                         }
 
-                        if !function_info.is_variadic{
-                            for (param, arg) in function_info.parameters.iter().zip(arguments) {
+                        if function_info.is_variadic && function_info.parameters.is_empty() {
+                            for arg in arguments {
+                                self.semantic_statement(arg);
+                            }
+                        } else {
+                            for (index, arg) in arguments.iter().enumerate() {
+                                let Some((param, param_type)) = function_info.parameter_at(index) else { continue };
                                 if let Some(arg_type) = self.semantic_statement(&arg) {
-                                    if arg_type != param.variable_type {
+                                    if arg_type != param_type {
                                         self.errors.push(
                                             ErrorContext::new(
                                                 ErrorCode::Semantic006,
-                                                None, None,
+                                                Some(*row), Some(*col),
                                                 vec![
                                                     ("variable_name", &param.name),
-                                                    ("variable_type", &param.variable_type.to_string()),
+                                                    ("variable_type", &param_type.to_string()),
                                                     ("value_type", &arg_type.to_string()),
                                                 ],
                                             )
@@ -147,10 +412,10 @@ impl Semantic {
                                     self.errors.push(
                                         ErrorContext::new(
                                             ErrorCode::Semantic006,
-                                            None, None,
+                                            Some(*row), Some(*col),
                                             vec![
                                                 ("variable_name", &param.name),
-                                                ("variable_type", &param.variable_type.to_string()),
+                                                ("variable_type", &param_type.to_string()),
                                                 ("value_type", "None"),
                                             ],
                                         )
@@ -167,7 +432,7 @@ impl Semantic {
                                     self.errors.push(
                                         ErrorContext::new(
                                             ErrorCode::Semantic005, 
-                                            None, None,
+                                            Some(*row), Some(*col),
                                             vec![("function_name", &name)],
                                         )
                                     );
@@ -177,22 +442,33 @@ impl Semantic {
                         }
                     },
                     None => {
-                        self.errors.push(
-                            ErrorContext::new(
-                                ErrorCode::Semantic004,
-                                None, None,
-                                vec![("function_name", &name)],
-                            )
-                        );
+                        let error = self.undefined_call_error("関数", name, *row, *col);
+                        self.errors.push(error);
                     },
                 }
                 return None
             },
-            PrivateNode::IfStatement { condition_node:_, then_block, else_block } => {
-                self.semantic_block(then_block);
+            PrivateNode::IfStatement { condition_node, then_block, else_block } => {
+                self.semantic_statement(condition_node);
+                if else_block.as_ref().is_some_and(|else_block| blocks_structurally_equal(then_block, else_block)) {
+                    let (row, col) = statement_position(statement);
+                    self.warnings.push(
+                        ErrorContext::new(
+                            ErrorCode::Warning003,
+                            row, col,
+                            vec![("name", &self.analysis_name)],
+                        )
+                    );
+                }
+
+                // 両方の分岐で初期化された変数のみを、if文の後で初期化済みとする
+                let initialized_before = self.initialized_variables.clone();
+                self.semantic_nested_block(then_block);
+                let initialized_then = std::mem::replace(&mut self.initialized_variables, initialized_before);
                 if let Some(else_block) = else_block {
-                    self.semantic_block(else_block);
+                    self.semantic_nested_block(else_block);
                 }
+                self.initialized_variables.retain(|name| initialized_then.contains(name));
             },
             PrivateNode::Literal { value } => {
                 match value {
@@ -200,18 +476,113 @@ impl Semantic {
                     LiteralValue::Float(_) => return Some(Type::Float),
                     LiteralValue::Int(_) => return Some(Type::Int),
                     LiteralValue::String(_) => return Some(Type::String),
+                    LiteralValue::Array(_) => return value.value_type(),
                     LiteralValue::Null => return None,
                 }
             },
+            PrivateNode::ArrayLiteral { elements } => {
+                let mut element_types = Vec::new();
+                for element in elements {
+                    element_types.push(self.semantic_statement(element)?);
+                }
+
+                let Some(element_type) = element_types.first().cloned() else {
+                    self.errors.push(
+                        ErrorContext::new(
+                            ErrorCode::Semantic017,
+                            None, None,
+                            vec![],
+                        )
+                    );
+                    return None
+                };
+                // 配列の要素は全て同じ型でなければならない
+                for value_type in &element_types {
+                    if *value_type != element_type {
+                        self.errors.push(
+                            ErrorContext::new(
+                                ErrorCode::Semantic014,
+                                None, None,
+                                vec![
+                                    ("expected", &element_type.to_string()),
+                                    ("actual", &value_type.to_string()),
+                                ],
+                            )
+                        );
+                        return None
+                    }
+                }
+                return Some(Type::Array(Box::new(element_type)))
+            },
+            PrivateNode::Conditional { condition, then_value, else_value } => {
+                let condition_type = self.semantic_statement(condition);
+                let then_type = self.semantic_statement(then_value);
+                let else_type = self.semantic_statement(else_value);
+
+                let (row, col) = Self::node_position(condition);
+                if let Some(condition_type) = condition_type.filter(|t| *t != Type::Bool) {
+                    self.errors.push(
+                        ErrorContext::new(
+                            ErrorCode::Semantic032,
+                            row, col,
+                            vec![("condition_type", &condition_type.to_string())],
+                        )
+                    );
+                    return None
+                }
+                let (then_type, else_type) = (then_type?, else_type?);
+                if then_type != else_type {
+                    self.errors.push(
+                        ErrorContext::new(
+                            ErrorCode::Semantic033,
+                            row, col,
+                            vec![
+                                ("then_type", &then_type.to_string()),
+                                ("else_type", &else_type.to_string()),
+                            ],
+                        )
+                    );
+                    return None
+                }
+                return Some(then_type)
+            },
+            PrivateNode::Index { array, index } => {
+                let array_type = self.semantic_statement(array)?;
+                let index_type = self.semantic_statement(index)?;
+
+                if index_type != Type::Int {
+                    self.errors.push(
+                        ErrorContext::new(
+                            ErrorCode::Semantic016,
+                            None, None,
+                            vec![("index_type", &index_type.to_string())],
+                        )
+                    );
+                    return None
+                }
+                match array_type {
+                    Type::Array(element_type) => return Some(*element_type),
+                    _ => {
+                        self.errors.push(
+                            ErrorContext::new(
+                                ErrorCode::Semantic015,
+                                None, None,
+                                vec![("value_type", &array_type.to_string())],
+                            )
+                        );
+                        return None
+                    },
+                }
+            },
             PrivateNode::Logical { operator, left, right } => {
-                if let Some(right) = right {
+                let operand_type = if let Some(right) = right {
                     match self.semantic_binary(&operator.to_string(), &left, &right) {
-                        Ok(value_type) => return Some(value_type),
+                        Ok(value_type) => value_type,
                         Err(_) => return None,
                     }
                 } else {
                     match self.semantic_statement(left) {
-                        Some(left_type) => return Some(left_type),
+                        Some(left_type) => left_type,
                         None => {
                             self.errors.push(
                                 ErrorContext::new(
@@ -223,26 +594,90 @@ impl Semantic {
                             return None
                         },
                     }
+                };
+
+                // 論理演算子（and, or, xor, not）はbool型のみを受け付ける
+                if operand_type != Type::Bool {
+                    self.errors.push(
+                        ErrorContext::new(
+                            ErrorCode::Semantic009,
+                            None, None,
+                            vec![
+                                ("operator", &operator.to_string()),
+                                ("operand", &operand_type.to_string()),
+                            ],
+                        )
+                    );
+                    return None
                 }
+                return Some(Type::Bool)
             },
-            PrivateNode::LoopStatement { condition_node:_, block } => {
-                self.semantic_block(block);
+            PrivateNode::LoopStatement { label:_, condition_node, block, else_block } => {
+                self.semantic_statement(condition_node);
+
+                // ループ本体は実行されない可能性があるため、本体内での初期化はループ後に持ち越さない
+                // else節もbreakした場合は実行されない
+                let initialized_before = self.initialized_variables.clone();
+                self.semantic_nested_block(block);
+                self.initialized_variables = initialized_before.clone();
+                if let Some(else_block) = else_block {
+                    self.semantic_nested_block(else_block);
+                    self.initialized_variables = initialized_before;
+                }
             },
             PrivateNode::ProcessComment { comment:_ } => {},
-            PrivateNode::ReturnStatement { assignalbe:_ } => {},
-            PrivateNode::Variable { name } => {
-                let function_info = match self.function_table.get_function_info(&self.analysis_name) {
-                    Some(function_info) => function_info,
+            PrivateNode::ReturnStatement { assignalbe } => {
+                let return_type = match self.function_table.get_function_info(&self.analysis_name) {
+                    Some(function_info) => function_info.return_type,
                     None => panic!("解析中の関数が存在しない"),
                 };
+                let value_type = self.semantic_statement(assignalbe);
 
-                match function_info.local_variables.get_type(name) {
+                match (return_type, value_type) {
+                    (None, _) => {
+                        self.errors.push(
+                            ErrorContext::new(
+                                ErrorCode::Semantic010,
+                                None, None,
+                                vec![("function_name", &self.analysis_name)],
+                            )
+                        );
+                    },
+                    (Some(return_type), Some(value_type)) if return_type != value_type => {
+                        self.errors.push(
+                            ErrorContext::new(
+                                ErrorCode::Semantic011,
+                                None, None,
+                                vec![
+                                    ("expected", &return_type.to_string()),
+                                    ("actual", &value_type.to_string()),
+                                    ("function_name", &self.analysis_name),
+                                ],
+                            )
+                        );
+                    },
+                    _ => {},
+                }
+            },
+            PrivateNode::Variable { name, row, col } => {
+                self.used_variables.insert(name.clone());
+                match self.get_local_variable_type(name) {
+                    Some(variable_type) if !self.initialized_variables.contains(name) => {
+                        self.errors.push(
+                            ErrorContext::new(
+                                ErrorCode::Semantic013,
+                                Some(*row), Some(*col),
+                                vec![("variable_name", name)],
+                            )
+                        );
+                        return Some(variable_type)
+                    },
                     Some(variable_type) => return Some(variable_type),
                     None => {
                         self.errors.push(
                             ErrorContext::new(
                                 ErrorCode::Semantic007,
-                                None, None,
+                                Some(*row), Some(*col),
                                 vec![("variable_name", name)],
                             )
                         );
@@ -250,19 +685,14 @@ impl Semantic {
                     }
                 }
             },
-            PrivateNode::VariableAssignment { name, expression } => {
-                let function_info = match self.function_table.get_function_info(&self.analysis_name) {
-                    Some(function_info) => function_info,
-                    None => panic!("解析中の関数が存在しない"),
-                };
-                
-                let variable_type = match function_info.local_variables.get_type(name) {
+            PrivateNode::VariableAssignment { name, expression, row, col } => {
+                let variable_type = match self.get_local_variable_type(name) {
                     Some(variable_type) => variable_type,
                     None => {
                         self.errors.push(
                             ErrorContext::new(
                                 ErrorCode::Semantic007,
-                                None, None,
+                                Some(*row), Some(*col),
                                 vec![("variable_name", name)],
                             )
                         );
@@ -270,7 +700,20 @@ impl Semantic {
                     },
                 };
 
-                match self.semantic_statement(&expression) {
+                if self.is_local_constant(name) {
+                    self.errors.push(
+                        ErrorContext::new(
+                            ErrorCode::Semantic028,
+                            Some(*row), Some(*col),
+                            vec![("variable_name", name)],
+                        )
+                    );
+                }
+
+                let value_type = self.semantic_statement(&expression);
+                self.initialized_variables.insert(name.clone());
+
+                match value_type {
                     Some(value_type) if value_type == variable_type => {
                         return Some(value_type)
                     },
@@ -278,7 +721,7 @@ impl Semantic {
                         self.errors.push(
                             ErrorContext::new(
                                 ErrorCode::Semantic006,
-                                None, None,
+                                Some(*row), Some(*col),
                                 vec![
                                     ("variable_name", &name),
                                     ("variable_type", &variable_type.to_string()),
@@ -292,7 +735,7 @@ impl Semantic {
                         self.errors.push(
                             ErrorContext::new(
                                 ErrorCode::Semantic006,
-                                None, None,
+                                Some(*row), Some(*col),
                                 vec![
                                     ("variable_name", &name),
                                     ("variable_type", &variable_type.to_string()),
@@ -304,26 +747,99 @@ impl Semantic {
                     },
                 }
             },
-            PrivateNode::VariableDeclaration { name, variable_type, initializer, doc:_ } => {
-                if let Some(function_info) = self.function_table.get_function_info_mut(&self.analysis_name) {
-                    function_info.local_variables.variable_declare(name, variable_type);
-                } else if let Some(coroutine_info) = self.coroutine_table.get_coroutine_info_mut(&self.analysis_name) {
-                    coroutine_info.local_variables.variable_declare(name, variable_type);
-                } else {
-                    println!("関数名: {}", self.analysis_name);
-                    panic!("解析中の関数が存在しない");
+            PrivateNode::ArrayDestructuring { names, variable_type, initializer, row, col } => {
+                let value_type = self.semantic_statement(initializer);
+
+                let Type::Array(element_type) = variable_type else {
+                    self.errors.push(
+                        ErrorContext::new(
+                            ErrorCode::Semantic026,
+                            Some(*row), Some(*col),
+                            vec![("variable_type", &variable_type.to_string())],
+                        )
+                    );
+                    return None
+                };
+                for name in names {
+                    self.declare_local_variable(name, element_type, false, *row, *col);
+                    self.initialized_variables.insert(name.clone());
+                }
+
+                if value_type.as_ref() != Some(variable_type) {
+                    self.errors.push(
+                        ErrorContext::new(
+                            ErrorCode::Semantic006,
+                            Some(*row), Some(*col),
+                            vec![
+                                ("variable_name", &names.join(", ")),
+                                ("variable_type", &variable_type.to_string()),
+                                ("value_type", &value_type.map_or("none".to_string(), |t| t.to_string())),
+                            ],
+                        )
+                    );
+                    return None
+                }
+                // 配列リテラルの場合のみ、長さを解析時に検査できる
+                if let PrivateNode::ArrayLiteral { elements } = initializer.as_ref() {
+                    if elements.len() != names.len() {
+                        self.errors.push(
+                            ErrorContext::new(
+                                ErrorCode::Semantic027,
+                                Some(*row), Some(*col),
+                                vec![
+                                    ("variables", &names.len().to_string()),
+                                    ("length", &elements.len().to_string()),
+                                ],
+                            )
+                        );
+                    }
+                }
+            },
+            PrivateNode::VariableDeclaration { name, variable_type: None, initializer, is_constant, doc:_, row, col } => {
+                // 型を省略した宣言は初期値の型で宣言する（初期値で同名の外側の変数を参照できるよう、解析後に宣言する）
+                let Some(value_type) = initializer.as_ref().and_then(|node| self.semantic_statement(node)) else {
+                    self.errors.push(
+                        ErrorContext::new(
+                            ErrorCode::Semantic038,
+                            Some(*row), Some(*col),
+                            vec![("variable_name", name)],
+                        )
+                    );
+                    return None
                 };
+                self.declare_local_variable(name, &value_type, *is_constant, *row, *col);
+                self.initialized_variables.insert(name.clone());
+                return Some(value_type)
+            },
+            PrivateNode::VariableDeclaration { name, variable_type: Some(variable_type), initializer, is_constant, doc:_, row, col } => {
+                self.declare_local_variable(name, variable_type, *is_constant, *row, *col);
+                if *is_constant && initializer.is_none() {
+                    self.errors.push(
+                        ErrorContext::new(
+                            ErrorCode::Semantic029,
+                            Some(*row), Some(*col),
+                            vec![("variable_name", name)],
+                        )
+                    );
+                }
                 
                 if let Some(node) = initializer {
-                    match self.semantic_statement(node) {
+                    let value_type = self.semantic_statement(node);
+                    self.initialized_variables.insert(name.clone());
+
+                    match value_type {
                         Some(value_type) if &value_type == variable_type => {
                             return Some(variable_type.clone())
                         },
+                        // 整数リテラルはfloat型へ昇格して初期化できる
+                        Some(Type::Int) if *variable_type == Type::Float && is_int_literal(node) => {
+                            return Some(variable_type.clone())
+                        },
                         Some(value_type) => {
                             self.errors.push(
                                 ErrorContext::new(
                                     ErrorCode::Semantic006,
-                                    None, None,
+                                    Some(*row), Some(*col),
                                     vec![
                                         ("variable_name", &name),
                                         ("variable_type", &variable_type.to_string()),
@@ -337,7 +853,7 @@ impl Semantic {
                             self.errors.push(
                                 ErrorContext::new(
                                     ErrorCode::Semantic006,
-                                    None, None,
+                                    Some(*row), Some(*col),
                                     vec![
                                         ("variable_name", &name),
                                         ("variable_type", &variable_type.to_string()),
@@ -348,47 +864,420 @@ impl Semantic {
                             return None
                         }
                     }
+                } else {
+                    self.initialized_variables.remove(name);
+                }
+            },
+            PrivateNode::Yield { value } => {
+                // 構文解析を経ずに組み立てたASTに備え、関数内のyieldもここで検出する
+                let Some(coroutine_info) = self.coroutine_table.get_coroutine_info(&self.analysis_name) else {
+                    self.errors.push(
+                        ErrorContext::new(
+                            ErrorCode::Semantic025,
+                            None, None,
+                            vec![("function_name", &self.analysis_name)],
+                        )
+                    );
+                    return None
+                };
+                let value_type = match value {
+                    Some(value) => Some(self.semantic_statement(value)?),
+                    None => None,
+                };
+                match (&coroutine_info.yield_type, value_type) {
+                    (None, None) => {},
+                    (Some(expected), Some(actual)) if *expected == actual => {},
+                    (None, Some(_)) => {
+                        self.errors.push(
+                            ErrorContext::new(
+                                ErrorCode::Semantic022,
+                                None, None,
+                                vec![("coroutine_name", &self.analysis_name)],
+                            )
+                        );
+                    },
+                    (Some(expected), actual) => {
+                        self.errors.push(
+                            ErrorContext::new(
+                                ErrorCode::Semantic023,
+                                None, None,
+                                vec![
+                                    ("expected", &expected.to_string()),
+                                    ("actual", &actual.map_or("none".to_string(), |t| t.to_string())),
+                                    ("coroutine_name", &self.analysis_name),
+                                ],
+                            )
+                        );
+                    },
                 }
             },
-            PrivateNode::Yield => {},
         }
         None
     }
 
-    fn semantic_binary(&mut self, operator:&str, left: &PrivateNode, right: &PrivateNode) -> Result<Type, ()> {
-        let left_type = match self.semantic_statement(left) {
-            Some(left_type) => left_type,
+    /// 定義されていない関数、コルーチンの呼び出しのエラー
+    /// 
+    /// `名前空間.名前`の場合は、名前空間と名前のどちらが存在しないかを区別する
+    fn undefined_call_error(&self, statement: &str, name: &str, row: u32, col: u32) -> ErrorContext {
+        match name.rsplit_once('.') {
+            Some((namespace, _)) if !self.function_table.has_namespace(namespace) && !self.coroutine_table.has_namespace(namespace) => {
+                ErrorContext::new(
+                    ErrorCode::Semantic019,
+                    Some(row), Some(col),
+                    vec![("namespace", namespace)],
+                )
+            },
+            Some((namespace, member)) => {
+                ErrorContext::new(
+                    ErrorCode::Semantic020,
+                    Some(row), Some(col),
+                    vec![
+                        ("namespace", namespace),
+                        ("statement", statement),
+                        ("name", member),
+                    ],
+                )
+            },
             None => {
+                ErrorContext::new(
+                    ErrorCode::Semantic004,
+                    Some(row), Some(col),
+                    vec![
+                        ("statement", statement),
+                        ("name", name),
+                    ],
+                )
+            },
+        }
+    }
+
+    /// abs関数の呼び出しの解析
+    /// 
+    /// 引数がintまたはfloatの1つであることを検査し、引数と同じ型を返す
+    fn semantic_abs(&mut self, arguments: &[PrivateNode], row: u32, col: u32) -> Option<Type> {
+        if arguments.len() != 1 {
+            self.errors.push(
+                ErrorContext::new(
+                    ErrorCode::Semantic008,
+                    Some(row), Some(col),
+                    vec![
+                        ("parameter", "1"),
+                        ("argument", &arguments.len().to_string()),
+                        ("name", "abs"),
+                        ("signature", "abs(x: int | float) -> int | float"),
+                    ],
+                )
+            );
+            return None
+        }
+
+        match self.semantic_statement(&arguments[0]) {
+            Some(arg_type @ (Type::Int | Type::Float)) => Some(arg_type),
+            arg_type => {
                 self.errors.push(
                     ErrorContext::new(
-                        ErrorCode::Semantic003,
-                        None, None,
-                        vec![("node", &format!("{:?}", left))],
+                        ErrorCode::Semantic006,
+                        Some(row), Some(col),
+                        vec![
+                            ("variable_name", "x"),
+                            ("variable_type", "int | float"),
+                            ("value_type", &arg_type.map_or("none".to_string(), |t| t.to_string())),
+                        ],
                     )
                 );
-                return Err(())
-            }
-        };
+                None
+            },
+        }
+    }
+
+    /// typeof関数の呼び出しの解析
+    /// 
+    /// 引数は任意の型の1つで、stringを返す
+    fn semantic_typeof(&mut self, arguments: &[PrivateNode], row: u32, col: u32) -> Option<Type> {
+        if arguments.len() != 1 {
+            self.errors.push(
+                ErrorContext::new(
+                    ErrorCode::Semantic008,
+                    Some(row), Some(col),
+                    vec![
+                        ("parameter", "1"),
+                        ("argument", &arguments.len().to_string()),
+                        ("name", "typeof"),
+                        ("signature", "typeof(x) -> string"),
+                    ],
+                )
+            );
+            return None
+        }
 
-        let right_type = match self.semantic_statement(right) {
-            Some(right_type) => right_type,
+        match self.semantic_statement(&arguments[0]) {
+            Some(_) => Some(Type::String),
             None => {
                 self.errors.push(
                     ErrorContext::new(
                         ErrorCode::Semantic003,
-                        None, None,
-                        vec![("node", &format!("{:?}", right))],
+                        Some(row), Some(col),
+                        vec![("node", &format!("{:?}", arguments[0]))],
+                    )
+                );
+                None
+            },
+        }
+    }
+
+    /// assert_eq関数の呼び出しの解析
+    /// 
+    /// 引数は同じ型の2つの値
+    fn semantic_assert_eq(&mut self, arguments: &Vec<PrivateNode>, row: u32, col: u32) {
+        let [left, right] = arguments.as_slice() else {
+            self.errors.push(
+                ErrorContext::new(
+                    ErrorCode::Semantic008,
+                    Some(row), Some(col),
+                    vec![
+                        ("parameter", "2"),
+                        ("argument", &arguments.len().to_string()),
+                        ("name", "assert_eq"),
+                        ("signature", "assert_eq(a: T, b: T)"),
+                    ],
+                )
+            );
+            return
+        };
+        let _ = self.semantic_binary("==", left, right);
+    }
+
+    /// format関数の呼び出しの解析
+    /// 
+    /// 第1引数はstring型のテンプレートで、続く引数は任意の型。プレースホルダーの数は実行時に検査する
+    fn semantic_format(&mut self, arguments: &[PrivateNode], row: u32, col: u32) -> Option<Type> {
+        let Some((template, values)) = arguments.split_first() else {
+            self.errors.push(
+                ErrorContext::new(
+                    ErrorCode::Semantic008,
+                    Some(row), Some(col),
+                    vec![
+                        ("parameter", "1"),
+                        ("argument", "0"),
+                        ("name", "format"),
+                        ("signature", "format(template: string, ...) -> string"),
+                    ],
+                )
+            );
+            return None
+        };
+
+        let template_type = self.semantic_statement(template);
+        for value in values {
+            self.semantic_statement(value);
+        }
+        if template_type != Some(Type::String) {
+            self.errors.push(
+                ErrorContext::new(
+                    ErrorCode::Semantic006,
+                    Some(row), Some(col),
+                    vec![
+                        ("variable_name", "template"),
+                        ("variable_type", "string"),
+                        ("value_type", &template_type.map_or("None".to_string(), |t| t.to_string())),
+                    ],
+                )
+            );
+            return None
+        }
+        Some(Type::String)
+    }
+
+    /// step関数の引数が生成済みのタスクであるか検査する
+    fn semantic_step(&mut self, arguments: &[PrivateNode], row: u32, col: u32) -> Option<Type> {
+        if arguments.len() != 1 {
+            self.errors.push(
+                ErrorContext::new(
+                    ErrorCode::Semantic008,
+                    Some(row), Some(col),
+                    vec![
+                        ("parameter", "1"),
+                        ("argument", &arguments.len().to_string()),
+                        ("name", "step"),
+                        ("signature", "step(task) -> string"),
+                    ],
+                )
+            );
+            return None
+        }
+
+        match &arguments[0] {
+            PrivateNode::Variable { name, .. } if self.task_table.get_task(name).is_some() => Some(Type::String),
+            argument => {
+                self.errors.push(
+                    ErrorContext::new(
+                        ErrorCode::Semantic024,
+                        Some(row), Some(col),
+                        vec![("argument", &format!("{:?}", argument))],
                     )
                 );
-                return Err(())
+                None
+            },
+        }
+    }
+
+    /// 解析中の関数またはコルーチンにローカル変数を宣言する
+    fn declare_local_variable(&mut self, name: &str, variable_type: &Type, is_constant: bool, row: u32, col: u32) {
+        if let Some(local_variables) = self.local_variables_mut() {
+            if is_constant {
+                local_variables.constant_declare(name, variable_type);
+            } else {
+                local_variables.variable_declare(name, variable_type);
             }
+        } else {
+            println!("関数名: {}", self.analysis_name);
+            panic!("解析中の関数が存在しない");
         };
+        self.declared_variables.push((name.to_string(), row, col));
+    }
 
-        if left_type != right_type {
+    /// 解析中の関数またはコルーチンのローカル変数
+    fn local_variables_mut(&mut self) -> Option<&mut VariableScope> {
+        if let Some(function_info) = self.function_table.get_function_info_mut(&self.analysis_name) {
+            Some(&mut function_info.local_variables)
+        } else if let Some(coroutine_info) = self.coroutine_table.get_coroutine_info_mut(&self.analysis_name) {
+            Some(&mut coroutine_info.local_variables)
+        } else {
+            None
+        }
+    }
+
+    /// 解析中の関数またはコルーチンの`const`で宣言された変数か
+    fn is_local_constant(&self, name: &str) -> bool {
+        if let Some(function_info) = self.function_table.get_function_info(&self.analysis_name) {
+            function_info.local_variables.is_constant(name)
+        } else if let Some(coroutine_info) = self.coroutine_table.get_coroutine_info(&self.analysis_name) {
+            coroutine_info.local_variables.is_constant(name)
+        } else {
+            false
+        }
+    }
+
+    /// 解析中の関数またはコルーチンのローカル変数の型を取得
+    fn get_local_variable_type(&self, name: &str) -> Option<Type> {
+        if let Some(function_info) = self.function_table.get_function_info(&self.analysis_name) {
+            function_info.local_variables.get_type(name)
+        } else if let Some(coroutine_info) = self.coroutine_table.get_coroutine_info(&self.analysis_name) {
+            coroutine_info.local_variables.get_type(name)
+        } else {
+            panic!("解析中の関数が存在しない")
+        }
+    }
+
+    /// ブロックの全ての経路がreturn文で終わるか
+    fn always_returns(block: &BlockNode) -> bool {
+        block.statements.iter().any(|statement| {
+            match statement {
+                PrivateNode::ReturnStatement { assignalbe:_ } => true,
+                // else節がない場合は、条件が偽の経路でreturnされない
+                PrivateNode::IfStatement { condition_node:_, then_block, else_block: Some(else_block) } => {
+                    Self::always_returns(then_block) && Self::always_returns(else_block)
+                },
+                // `while (true)`はbreakしない限りreturnでしか抜けられない
+                PrivateNode::LoopStatement { label:_, condition_node, block, else_block:_ } => {
+                    matches!(condition_node.as_ref(), PrivateNode::Literal { value: LiteralValue::Bool(true) })
+                    && !Self::contains_break(block)
+                },
+                _ => false,
+            }
+        })
+    }
+
+    /// ブロック内（ネストしたブロックを含む）にbreak文が存在するか
+    fn contains_break(block: &BlockNode) -> bool {
+        block.statements.iter().any(|statement| {
+            match statement {
+                PrivateNode::Break { label:_ } => true,
+                PrivateNode::IfStatement { condition_node:_, then_block, else_block } => {
+                    Self::contains_break(then_block)
+                    || else_block.as_ref().is_some_and(Self::contains_break)
+                },
+                // else節のbreakは外側のループが対象
+                PrivateNode::LoopStatement { label:_, condition_node:_, block, else_block } => {
+                    Self::contains_break(block)
+                    || else_block.as_ref().is_some_and(Self::contains_break)
+                },
+                _ => false,
+            }
+        })
+    }
+
+    /// ノードのソース上の位置（演算は左辺の位置）
+    fn node_position(node: &PrivateNode) -> (Option<u32>, Option<u32>) {
+        match node {
+            PrivateNode::Variable { row, col, .. }
+            | PrivateNode::FunctionCall { row, col, .. } => (Some(*row), Some(*col)),
+            PrivateNode::Compare { left, .. }
+            | PrivateNode::Arithmetic { left, .. }
+            | PrivateNode::Logical { left, .. } => Self::node_position(left),
+            PrivateNode::Index { array, .. } => Self::node_position(array),
+            PrivateNode::Conditional { condition, .. } => Self::node_position(condition),
+            _ => (None, None),
+        }
+    }
+
+    /// 文字列の連結（`~`）の意味解析
+    /// 
+    /// 両辺は型が異なってもよいが、数値か文字列である必要がある
+    fn semantic_concat(&mut self, left: &PrivateNode, right: &PrivateNode) -> Result<Type, ()> {
+        let mut result = Ok(Type::String);
+        for operand in [left, right] {
+            match self.operand_type(operand) {
+                Some(Type::Int | Type::Float | Type::String) => {},
+                Some(operand_type) => {
+                    let (row, col) = Self::node_position(operand);
+                    self.errors.push(
+                        ErrorContext::new(
+                            ErrorCode::Semantic031,
+                            row, col,
+                            vec![("type", &operand_type.to_string())],
+                        )
+                    );
+                    result = Err(());
+                },
+                None => result = Err(()),
+            }
+        }
+        result
+    }
+
+    /// 演算の対象となる値の型
+    /// 
+    /// 関数呼び出しの場合は、戻り値がないことなどのエラーを解析時に報告済みのため、重ねて報告しない
+    fn operand_type(&mut self, operand: &PrivateNode) -> Option<Type> {
+        let operand_type = self.semantic_statement(operand);
+        if operand_type.is_none() && !matches!(operand, PrivateNode::FunctionCall { .. }) {
             self.errors.push(
                 ErrorContext::new(
-                    ErrorCode::Semantic002,
+                    ErrorCode::Semantic003,
                     None, None,
+                    vec![("node", &format!("{:?}", operand))],
+                )
+            );
+        }
+        operand_type
+    }
+
+    fn semantic_binary(&mut self, operator:&str, left: &PrivateNode, right: &PrivateNode) -> Result<Type, ()> {
+        let left_type = self.operand_type(left).ok_or(())?;
+        let right_type = self.operand_type(right).ok_or(())?;
+
+        if left_type != right_type {
+            // 等値比較は型が異なると必ず不一致になるため、専用のエラーにする
+            let error_code = match operator {
+                "==" | "!=" => ErrorCode::Semantic018,
+                _ => ErrorCode::Semantic002,
+            };
+            let (row, col) = Self::node_position(left);
+            self.errors.push(
+                ErrorContext::new(
+                    error_code,
+                    row, col,
                     vec![
                         ("left", &left_type.to_string()),
                         ("operator", operator),
@@ -403,9 +1292,41 @@ impl Semantic {
     }
 }
 
-pub fn semantic(ast: &RootNode) -> Result<Semantic, Vec<ErrorContext>> {
+/// 意味解析を行い、エラーの有無に関わらず解析結果を返す
+/// 
+/// エラーと警告の両方を参照する場合に使う
+pub fn analyze(ast: &RootNode) -> Semantic {
     let mut semantic = Semantic::new();
     semantic.semantic(&ast);
+    semantic
+}
+
+/// 整数リテラル（符号付きを含む）か
+fn is_int_literal(node: &PrivateNode) -> bool {
+    match node {
+        PrivateNode::Literal { value: LiteralValue::Int(_) } => true,
+        PrivateNode::Arithmetic { operator: Arithmetic::Minus, left, right: None } => is_int_literal(left),
+        _ => false,
+    }
+}
+
+/// 文の位置情報（位置情報を持たない文の場合は、含まれる式の位置情報）
+fn statement_position(statement: &PrivateNode) -> (Option<u32>, Option<u32>) {
+    match statement {
+        PrivateNode::CoroutineInstantiation { row, col, .. }
+        | PrivateNode::VariableDeclaration { row, col, .. }
+        | PrivateNode::VariableAssignment { row, col, .. }
+        | PrivateNode::ArrayDestructuring { row, col, .. } => (Some(*row), Some(*col)),
+        PrivateNode::ExpressionStatement { expression } => statement_position(expression),
+        PrivateNode::ReturnStatement { assignalbe } => statement_position(assignalbe),
+        PrivateNode::IfStatement { condition_node, .. }
+        | PrivateNode::LoopStatement { condition_node, .. } => statement_position(condition_node),
+        _ => Semantic::node_position(statement),
+    }
+}
+
+pub fn semantic(ast: &RootNode) -> Result<Semantic, Vec<ErrorContext>> {
+    let semantic = analyze(ast);
 
     if semantic.errors.is_empty() {
         return Ok(semantic)
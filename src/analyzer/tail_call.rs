@@ -0,0 +1,62 @@
+use crate::{analyzer::function_table::BUILTIN_FUNCTIONS, parser::node::{BlockNode, PrivateNode}};
+
+/// 末尾位置の関数呼び出し（`return f(args);`）
+#[derive(Debug, Clone, PartialEq)]
+pub struct TailCall {
+    /// 呼び出す関数名
+    pub name: String,
+    /// 自身を呼び出す末尾再帰か
+    pub is_recursive: bool,
+    pub row: u32,
+    pub col: u32,
+}
+
+/// 関数の処理から末尾位置の関数呼び出しを検出する
+/// 
+/// 末尾呼び出し最適化の準備として、呼び出し結果をそのまま返す`return`を記録する。組み込み関数の呼び出しは対象外
+/// 
+/// ## Argments
+/// 
+/// - `function_name` - 解析する関数名（末尾再帰の判定に使う）
+/// - `block` - 関数の処理
+pub fn find_tail_calls(function_name: &str, block: &BlockNode) -> Vec<TailCall> {
+    let mut tail_calls = Vec::new();
+    collect_block(function_name, block, &mut tail_calls);
+    tail_calls
+}
+
+fn collect_block(function_name: &str, block: &BlockNode, tail_calls: &mut Vec<TailCall>) {
+    for statement in &block.statements {
+        match statement {
+            PrivateNode::ReturnStatement { assignalbe } => collect_expression(function_name, assignalbe, tail_calls),
+            PrivateNode::IfStatement { then_block, else_block, .. }
+            | PrivateNode::LoopStatement { block: then_block, else_block, .. } => {
+                collect_block(function_name, then_block, tail_calls);
+                if let Some(else_block) = else_block {
+                    collect_block(function_name, else_block, tail_calls);
+                }
+            },
+            _ => {},
+        }
+    }
+}
+
+/// 返す式のうち、値がそのまま戻り値になる位置の関数呼び出しを記録する
+fn collect_expression(function_name: &str, expression: &PrivateNode, tail_calls: &mut Vec<TailCall>) {
+    match expression {
+        PrivateNode::FunctionCall { name, row, col, .. } if !BUILTIN_FUNCTIONS.contains(&name.as_str()) => {
+            tail_calls.push(TailCall {
+                name: name.clone(),
+                is_recursive: name == function_name,
+                row: *row,
+                col: *col,
+            });
+        },
+        // 条件式はどちらかの値がそのまま戻り値になる
+        PrivateNode::Conditional { then_value, else_value, .. } => {
+            collect_expression(function_name, then_value, tail_calls);
+            collect_expression(function_name, else_value, tail_calls);
+        },
+        _ => {},
+    }
+}
@@ -1,4 +1,4 @@
-use crate::parser::node::{BlockNode, PrivateNode};
+use crate::{common::types::GreenValue, parser::node::{BlockNode, PrivateNode}};
 use std::collections::HashMap;
 
 #[derive(Debug, Clone)]
@@ -13,30 +13,58 @@ pub enum TaskStatus {
     Completed,
 }
 
+#[derive(Debug, Clone)]
+pub enum FrameKind {
+    /// コルーチン本体、if文のブロック
+    Block,
+    /// ループのブロック（ブロックの末尾で条件を再評価する）
+    Loop {
+        label: Option<String>,
+        condition: PrivateNode,
+        /// 条件が偽になって終了した場合に実行するブロック
+        else_block: Option<BlockNode>,
+    },
+}
+
+/// 実行中のブロックと、その中での実行位置
+#[derive(Debug, Clone)]
+pub struct TaskFrame {
+    pub kind: FrameKind,
+    pub statements: Vec<PrivateNode>,
+    pub position: usize,
+}
+impl TaskFrame {
+    pub fn new(kind: FrameKind, block: &BlockNode) -> Self {
+        Self {
+            kind,
+            statements: block.statements.clone(),
+            position: 0,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct CoroutineTask {
     pub task_name: String,
     pub coroutine_name: String,
     pub status: TaskStatus,
-    pub current_position: usize,
-    pub process: Vec<PrivateNode>,
+    /// ネストしたブロックの実行位置（末尾が最も内側）
+    /// 
+    /// yieldで中断した位置から再開するために、ブロックごとに保持する
+    pub frames: Vec<TaskFrame>,
+    /// コルーチンのローカル変数
+    /// 
+    /// 再開時にインタプリタのスコープへ移し、中断時に書き戻す
+    pub variables: HashMap<String, GreenValue>,
 }
 impl CoroutineTask {
     pub fn new(task_name: &str, coroutine_name: &str, process: &BlockNode) -> Self {
-        let process = process.statements.clone();
         Self {
             task_name: task_name.to_string(),
             coroutine_name: coroutine_name.to_string(),
             status: TaskStatus::Ready,
-            current_position: 0,
-            process,
-        }
-    }
-
-    pub fn step(&mut self) {
-        self.current_position += 1;
-        if self.current_position >= self.process.len() {
-            self.status = TaskStatus::Completed;
+            frames: vec![TaskFrame::new(FrameKind::Block, process)],
+            variables: HashMap::new(),
         }
     }
 }
@@ -4,40 +4,104 @@ use crate::common::types::Type;
 pub struct VariableInfo {
     name: String,
     variable_type: Type,
+    /// `const`で宣言された（再代入できない）
+    is_constant: bool,
+    /// 宣言されたブロックの深さ（関数、コルーチンの直下が0）
+    depth: usize,
+    /// 宣言されたブロックを抜けていないか
+    visible: bool,
 }
 
 #[derive(Debug, Clone)]
 pub struct VariableScope {
     variable_info: Vec<VariableInfo>,
     parent_pointer: Option<usize>,
+    /// 解析中のブロックの深さ
+    depth: usize,
 }
 impl VariableScope {
     pub fn new(parent_pointer: Option<usize>) -> Self {
         Self {
             variable_info: Vec::new(),
             parent_pointer,
+            depth: 0,
         }
     }
 
+    /// if文、ループのブロックに入る
+    pub fn enter_block(&mut self) {
+        self.depth += 1;
+    }
+
+    /// if文、ループのブロックを抜け、ブロック内で宣言された変数を参照できなくする
+    /// 
+    /// 宣言の記録は変数の一覧と数のために残す
+    pub fn leave_block(&mut self) {
+        for variable in &mut self.variable_info {
+            if variable.depth == self.depth {
+                variable.visible = false;
+            }
+        }
+        self.depth = self.depth.saturating_sub(1);
+    }
+
     /// 変数定義
     pub fn variable_declare(&mut self, name: &str, var_type: &Type) {
+        self.declare(name, var_type, false);
+    }
+
+    /// 定数定義
+    pub fn constant_declare(&mut self, name: &str, var_type: &Type) {
+        self.declare(name, var_type, true);
+    }
+
+    fn declare(&mut self, name: &str, var_type: &Type, is_constant: bool) {
         self.variable_info.push(
             VariableInfo {
                 name: name.to_string(),
                 variable_type: var_type.clone(),
+                is_constant,
+                depth: self.depth,
+                visible: true,
             }
         );
     }
 
+    /// 参照できる変数の情報（内側のブロックで宣言された変数を優先する）
+    fn get_info(&self, name: &str) -> Option<&VariableInfo> {
+        self.variable_info.iter().rev().find(|variable| variable.visible && variable.name == name)
+    }
+
     /// 変数呼び出し（型情報を返す）
     pub fn get_type(&self, name: &str) -> Option<Type> {
+        self.get_info(name).map(|variable| variable.variable_type.clone())
+    }
+
+    /// `const`で宣言された変数か
+    pub fn is_constant(&self, name: &str) -> bool {
+        self.get_info(name).is_some_and(|variable| variable.is_constant)
+    }
+
+    /// 宣言された変数の数
+    pub fn variable_count(&self) -> usize {
+        self.variable_info.len()
+    }
+
+    /// スコープと変数の一覧を出力用の文字列にする
+    /// 
+    /// if文、ループのブロック内で宣言された変数は、ブロックの深さだけ字下げする
+    pub fn dump(&self, index: usize, indent: &str) -> String {
+        let parent = match self.parent_pointer {
+            Some(pointer) => format!("#{}", pointer),
+            None => "none".to_string(),
+        };
+        let mut dump = format!("{}scope #{} (parent: {})\n", indent, index, parent);
         for variable in &self.variable_info {
-            if variable.name == name {
-                return Some(variable.variable_type.clone())
-            }
+            let constant = if variable.is_constant { " (const)" } else { "" };
+            let block_indent = "  ".repeat(variable.depth);
+            dump.push_str(&format!("{}  {}{}: {}{}\n", indent, block_indent, variable.name, variable.variable_type.to_string(), constant));
         }
-
-        return None
+        dump
     }
 }
 
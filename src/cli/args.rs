@@ -1,11 +1,15 @@
-use clap::{Parser, ArgGroup};
+use clap::{Parser, ArgGroup, ValueEnum};
+use crate::{error::color::ColorChoice, interpreter::output::LineEnding, lexer::lexical_analyzer};
+
+/// `--max-call-depth`に指定できる上限（呼び出しごとにスタックを確保するため）
+const MAX_CALL_DEPTH_LIMIT: u64 = 100_000;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about)]
 #[command(group(
     ArgGroup::new("mode")
         .required(false)
-        .args(&["execute", "analyze"])
+        .args(&["execute", "analyze", "repl", "dump_scopes", "ast_hash", "ast", "check", "stop_after", "dump_tokens_with_trivia"])
 ))]
 pub struct Cli {
     /// The input file to process
@@ -19,4 +23,99 @@ pub struct Cli {
     /// Analyze the script without execution
     #[arg(short, long)]
     pub analyze: bool,
+
+    /// Check the script for errors without executing it
+    #[arg(long)]
+    pub check: bool,
+
+    /// Stop after the given phase and print its result
+    #[arg(long, value_enum, value_name = "PHASE")]
+    pub stop_after: Option<Phase>,
+
+    /// Print every token including comments and whitespace with its position and exit
+    #[arg(long)]
+    pub dump_tokens_with_trivia: bool,
+
+    /// Start an interactive session instead of reading a file
+    #[arg(long)]
+    pub repl: bool,
+
+    /// Print the variable scopes of each function and coroutine after analysis
+    #[arg(long)]
+    pub dump_scopes: bool,
+
+    /// Print the parsed AST as an indented tree and exit
+    #[arg(long)]
+    pub ast: bool,
+
+    /// Print a hash of the AST that ignores whitespace, comments and docs
+    #[arg(long)]
+    pub ast_hash: bool,
+
+    /// Treat warnings as errors
+    #[arg(long, visible_alias = "strict")]
+    pub deny_warnings: bool,
+
+    /// Fail if any `@todo` remains in doc or process comments
+    #[arg(long)]
+    pub fail_on_todo: bool,
+
+    /// Disable colored error output (same as `--color never`)
+    #[arg(long, conflicts_with = "color")]
+    pub no_color: bool,
+
+    /// When to color error output (`auto` colors only when stdout is a terminal)
+    #[arg(long, value_enum, value_name = "WHEN", default_value = "auto")]
+    pub color: ColorChoice,
+
+    /// Output path of the analyze result
+    #[arg(short, long, value_name = "PATH", default_value_t = String::from("analyze.json"))]
+    pub output: String,
+
+    /// Write the analyze result as indented JSON (default)
+    #[arg(long, conflicts_with = "json_compact")]
+    pub json_pretty: bool,
+
+    /// Write the analyze result as JSON without extra whitespace
+    #[arg(long)]
+    pub json_compact: bool,
+
+    /// Attach constant-folded arguments to calls in the analyze output
+    #[arg(long)]
+    pub fold_constants: bool,
+
+    /// Print the output of `debug` calls to stderr
+    #[arg(short, long)]
+    pub debug: bool,
+
+    /// Abort with a runtime error once `print` output exceeds this many bytes
+    #[arg(long, value_name = "N")]
+    pub max_output_bytes: Option<usize>,
+
+    /// Abort with a runtime error once function calls nest deeper than this (at most 100000)
+    #[arg(long, value_name = "N", default_value_t = 1000, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..=MAX_CALL_DEPTH_LIMIT))]
+    pub max_call_depth: usize,
+
+    /// Print floats with this many decimal places (internal values are not rounded)
+    #[arg(long, value_name = "N")]
+    pub float_precision: Option<usize>,
+
+    /// Reject identifiers and string literals longer than this many characters
+    #[arg(long, value_name = "N", default_value_t = lexical_analyzer::DEFAULT_MAX_TOKEN_LENGTH)]
+    pub max_identifier_length: usize,
+
+    /// Line terminator written after each `print`
+    #[arg(long, value_enum, default_value = "lf")]
+    pub eol: LineEnding,
+}
+
+/// `--stop-after`で指定する処理の段階
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Phase {
+    /// 字句解析（トークンを表示する）
+    Lex,
+    /// 構文解析（ASTを表示する）
+    Parse,
+    /// 意味解析（診断のみを表示する）
+    Analyze,
 }
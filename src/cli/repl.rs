@@ -0,0 +1,209 @@
+use std::io::{self, BufRead, Write};
+use crate::{
+    analyzer::semantic::{self, Semantic},
+    error::{
+        error_code::ErrorCode, error_context::ErrorContext, error_message::ErrorMessage
+    },
+    interpreter::execute::{ExecuteOption, Interpreter},
+    lexer::lexical_analyzer,
+    parser::{node::PrivateNode, parser},
+};
+
+/// 入力された文をまとめて意味解析するための関数名
+const REPL_FUNCTION: &str = "__repl__";
+
+/// REPLの実行
+///
+/// 関数・コルーチンの定義とトップレベルの変数はセッション中保持される
+/// `:vars`で現在の変数の一覧を表示し、`:quit`またはEOFで終了する
+pub fn run(option: &ExecuteOption) -> Result<(), String> {
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    // 解析に成功した定義と、実行に成功した文の履歴
+    let mut definitions = String::new();
+    let mut history = String::new();
+
+    let semantic = analyze(&wrap_statements("", "")).map_err(|errors| errors.join("\n"))?;
+    let mut interpreter = Interpreter::new(&semantic, option);
+
+    while let Some(entry) = read_entry(&mut lines) {
+        let trimmed = entry.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if trimmed == ":quit" {
+            break;
+        }
+        if trimmed == ":vars" {
+            print_variables(&interpreter);
+            continue;
+        }
+
+        if is_definition(trimmed) {
+            let candidate = format!("{}{}\n", definitions, entry);
+            match analyze(&format!("{}{}", candidate, wrap_statements("", &history))) {
+                Ok(semantic) => {
+                    interpreter.update_semantic(&semantic);
+                    definitions = candidate;
+                },
+                Err(errors) => print_errors(&errors),
+            }
+            continue;
+        }
+
+        // 式を評価しやすいよう、末尾のセミコロンは省略できる
+        let entry = if trimmed.ends_with(';') || trimmed.ends_with('}') {
+            entry
+        } else {
+            format!("{};\n", trimmed)
+        };
+
+        let source = format!("{}{}", definitions, wrap_statements(&history, &entry));
+        let semantic = match analyze(&source) {
+            Ok(semantic) => semantic,
+            Err(errors) => {
+                print_errors(&errors);
+                continue;
+            },
+        };
+        let statements = match parse_statements(&entry) {
+            Ok(statements) => statements,
+            Err(errors) => {
+                print_errors(&errors);
+                continue;
+            },
+        };
+
+        interpreter.update_semantic(&semantic);
+        if execute_entry(&mut interpreter, &statements) {
+            history.push_str(&entry);
+            history.push('\n');
+        }
+    }
+
+    Ok(())
+}
+
+/// 入力を1件読み込む
+///
+/// 波括弧が閉じるまで複数行を1件として扱う
+fn read_entry(lines: &mut impl Iterator<Item = io::Result<String>>) -> Option<String> {
+    let mut entry = String::new();
+    let mut depth = 0;
+    loop {
+        print!("{}", if entry.is_empty() { "> " } else { "... " });
+        let _ = io::stdout().flush();
+
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            _ => return if entry.is_empty() { None } else { Some(entry) },
+        };
+        depth += brace_depth(&line);
+        entry.push_str(&line);
+        entry.push('\n');
+
+        if depth <= 0 {
+            return Some(entry)
+        }
+    }
+}
+
+/// 文字列リテラルの外にある波括弧の増減
+fn brace_depth(line: &str) -> i32 {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in line.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '{' if !in_string => depth += 1,
+            '}' if !in_string => depth -= 1,
+            _ => {},
+        }
+    }
+    depth
+}
+
+fn is_definition(entry: &str) -> bool {
+    entry.starts_with("function ") || entry.starts_with("coroutine ")
+}
+
+/// 文を意味解析用の関数で包む
+fn wrap_statements(history: &str, entry: &str) -> String {
+    format!("function {}() {{\n{}{}\n}}\n", REPL_FUNCTION, history, entry)
+}
+
+/// 字句解析から意味解析まで
+fn analyze(source: &str) -> Result<Semantic, Vec<String>> {
+    let (tokens, errors) = lexical_analyzer::lex(source);
+    if !errors.is_empty() {
+        return Err(to_messages(errors))
+    }
+    let (ast, errors) = parser::parse(tokens);
+    if !errors.is_empty() {
+        return Err(to_messages(errors))
+    }
+    semantic::semantic(&ast).map_err(to_messages)
+}
+
+/// 入力された文の構文解析
+fn parse_statements(entry: &str) -> Result<Vec<PrivateNode>, Vec<String>> {
+    let (tokens, errors) = lexical_analyzer::lex(&wrap_statements("", entry));
+    if !errors.is_empty() {
+        return Err(to_messages(errors))
+    }
+    let (ast, errors) = parser::parse(tokens);
+    if !errors.is_empty() {
+        return Err(to_messages(errors))
+    }
+    Ok(ast.functions.into_iter()
+        .find(|function| function.name == REPL_FUNCTION)
+        .map(|function| function.block.statements)
+        .unwrap_or_default())
+}
+
+/// 文を順に実行し、式文の結果を表示する
+///
+/// ## Return
+///
+/// - 全ての文の実行に成功したか
+fn execute_entry(interpreter: &mut Interpreter, statements: &[PrivateNode]) -> bool {
+    for statement in statements {
+        match interpreter.execute_statement(statement) {
+            Ok(Some(value)) => println!("{}", value.value.to_string()),
+            Ok(None) => {},
+            Err(e) => {
+                let error = ErrorContext::new(
+                    ErrorCode::Runtime001,
+                    None, None,
+                    vec![("message", &e)],
+                );
+                print_errors(&to_messages(vec![error]));
+                return false
+            },
+        }
+    }
+    true
+}
+
+/// 変数を`名前: 型 = 値`の形式で表示する
+fn print_variables(interpreter: &Interpreter) {
+    for (name, variable) in interpreter.variables() {
+        println!("{}: {} = {}", name, variable.value_type.to_string(), variable.value.to_string());
+    }
+}
+
+fn to_messages(errors: Vec<ErrorContext>) -> Vec<String> {
+    errors.into_iter()
+        .map(|error| ErrorMessage::global().get_error_message(error).unwrap_or_else(|e| e))
+        .collect()
+}
+
+fn print_errors(errors: &[String]) {
+    for error in errors {
+        println!("{}", error);
+    }
+}
@@ -34,26 +34,32 @@ impl ControlKeyword {
 #[derive(Debug, Clone, PartialEq)]
 pub enum DeclarationKeyword {
     Let,
+    Const,
     Function,
     Coroutine,
     Coro,
+    Import,
 }
 impl DeclarationKeyword {
     pub fn from_str(str: &str) -> Option<Self> {
         match str {
             "let" => Some(Self::Let),
+            "const" => Some(Self::Const),
             "function" => Some(Self::Function),
             "coroutine" => Some(Self::Coroutine),
             "coro" => Some(Self::Coro),
+            "import" => Some(Self::Import),
             _ => None,
         }
     }
     pub fn to_string(&self) -> String {
         let str = match self {
             Self::Let => "let",
+            Self::Const => "const",
             Self::Function => "function",
             Self::Coroutine => "coroutine",
             Self::Coro => "coro",
+            Self::Import => "import",
         };
         str.to_string()
     }
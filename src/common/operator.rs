@@ -51,6 +51,8 @@ pub enum Arithmetic {
     Minus,
     Multiply,
     Divide,
+    /// 文字列の連結（数値は文字列に変換する）
+    Concat,
 }
 impl Arithmetic {
     pub fn from_str(str: &str) -> Option<Self> {
@@ -59,6 +61,7 @@ impl Arithmetic {
             "-" => Some(Self::Minus),
             "*" => Some(Self::Multiply),
             "/" => Some(Self::Divide),
+            "~" => Some(Self::Concat),
             _ => None,
         }
     }
@@ -68,6 +71,7 @@ impl Arithmetic {
             Self::Minus => "-",
             Self::Multiply => "*",
             Self::Divide => "/",
+            Self::Concat => "~",
         };
         operator.to_string()
     }
@@ -8,6 +8,8 @@ pub enum Type {
     Bool,
     String,
     Coroutine,
+    /// 配列（要素の型）
+    Array(Box<Type>),
 }
 impl Type {
     pub fn from_keyword(type_name: &TypeName) -> Self {
@@ -25,6 +27,7 @@ impl Type {
             Self::Bool => "bool".to_string(),
             Self::String => "string".to_string(),
             Self::Coroutine => "coroutine".to_string(),
+            Self::Array(element_type) => format!("{}[]", element_type.to_string()),
         }
     }
 }
@@ -36,19 +39,73 @@ pub enum LiteralValue {
     Int(i32),
     Bool(bool),
     String(String),
+    Array(Vec<LiteralValue>),
     Null,
 }
 
 impl LiteralValue {
+    /// 値の文字列表現
+    /// 
+    /// 浮動小数点数の非数は`nan`、無限大は`inf`、`-inf`と表記する
     pub fn to_string(&self) -> String {
         match self {
             Self::Int(i) => i.to_string(),
+            Self::Float(f) if f.is_nan() => "nan".to_string(),
             Self::Float(f) => f.to_string(),
             Self::String(s) => s.clone(),
             Self::Bool(b) => b.to_string(),
+            Self::Array(elements) => {
+                let elements = elements.iter().map(|x| x.to_string()).collect::<Vec<_>>().join(", ");
+                format!("[{}]", elements)
+            },
             Self::Null => "Null".to_string(),
         }
     }
+
+    /// 出力用の値の表現
+    /// 
+    /// `float_precision`を指定した場合、有限の浮動小数点数を小数点以下その桁数で表記する（配列の要素も同様）
+    pub fn to_output_string(&self, float_precision: Option<usize>) -> String {
+        match (self, float_precision) {
+            (Self::Float(f), Some(precision)) if f.is_finite() => format!("{:.*}", precision, f),
+            (Self::Array(elements), Some(_)) => {
+                let elements = elements.iter().map(|x| x.to_output_string(float_precision)).collect::<Vec<_>>().join(", ");
+                format!("[{}]", elements)
+            },
+            _ => self.to_string(),
+        }
+    }
+
+    /// エラーメッセージ用の値の表現
+    /// 
+    /// 型を併記し（`true（bool）`）、文字列は引用符で囲んで他の型の値と区別する
+    pub fn describe(&self) -> String {
+        let value = match self {
+            Self::String(s) => format!("{:?}", s),
+            _ => self.to_string(),
+        };
+        match self.value_type() {
+            Some(value_type) => format!("{}（{}）", value, value_type.to_string()),
+            None => value,
+        }
+    }
+
+    /// 値の型を取得する
+    /// 
+    /// `Null`と空の配列は型が定まらないため`None`を返す
+    pub fn value_type(&self) -> Option<Type> {
+        match self {
+            Self::Int(_) => Some(Type::Int),
+            Self::Float(_) => Some(Type::Float),
+            Self::String(_) => Some(Type::String),
+            Self::Bool(_) => Some(Type::Bool),
+            Self::Array(elements) => {
+                let element_type = elements.first()?.value_type()?;
+                Some(Type::Array(Box::new(element_type)))
+            },
+            Self::Null => None,
+        }
+    }
 }
 
 /// Green言語の値
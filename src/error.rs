@@ -1,3 +1,4 @@
 pub mod error_message;
 pub mod error_code;
-pub mod error_context;
\ No newline at end of file
+pub mod error_context;
+pub mod color;
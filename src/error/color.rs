@@ -0,0 +1,51 @@
+use std::{io::IsTerminal, sync::OnceLock};
+use regex::Regex;
+use super::{error_context::ErrorContext, error_message::ErrorMessage};
+
+const RED: &str = "\x1b[31m";
+const CYAN: &str = "\x1b[36m";
+const RESET: &str = "\x1b[0m";
+
+/// エラー出力を色付けする条件（`--color`）
+#[derive(Debug, Clone, Copy, Default, PartialEq, clap::ValueEnum)]
+pub enum ColorChoice {
+    /// 標準出力が端末の場合のみ
+    #[default]
+    Auto,
+    /// 常に色付けする（テストなどで端末の判定を無視する）
+    Always,
+    /// 色付けしない
+    Never,
+}
+
+/// エラー出力を色付けするか
+/// 
+/// `--no-color`が指定された場合は`Never`と同じ
+pub fn enabled(choice: ColorChoice, no_color: bool) -> bool {
+    match choice {
+        _ if no_color => false,
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => std::io::stdout().is_terminal(),
+    }
+}
+
+/// 位置（`N行 M列目`）に一致する正規表現
+fn location_pattern() -> &'static Regex {
+    static LOCATION: OnceLock<Regex> = OnceLock::new();
+    LOCATION.get_or_init(|| Regex::new(r"\d+行 \d+列目").unwrap())
+}
+
+/// エラーメッセージを作成し、色付けする
+/// 
+/// 色付けが有効な場合は、先頭にエラーコードを赤で付け、位置（`N行 M列目`）を水色にする。無効な場合はメッセージをそのまま返す
+pub fn paint_error(error: ErrorContext, enabled: bool) -> Result<String, String> {
+    let code = error.error_code.to_string();
+    let message = ErrorMessage::global().get_error_message(error)?;
+    if !enabled {
+        return Ok(message)
+    }
+
+    let message = location_pattern().replace_all(&message, format!("{}$0{}", CYAN, RESET).as_str());
+    Ok(format!("{}{}{} {}", RED, code, RESET, message))
+}
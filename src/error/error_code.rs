@@ -2,6 +2,12 @@
 pub enum ErrorCode {
     /// ファイルが見つからない
     Io001,
+    /// インポートするファイルが見つからない
+    Io002,
+    /// 解析結果を出力できない
+    Io003,
+    /// ファイルがUTF-8として不正
+    Io004,
 
     /// 字句エラー
     Lex001,
@@ -13,6 +19,20 @@ pub enum ErrorCode {
     Lex004,
     /// 未定義の演算子
     Lex005,
+    /// 識別子に使えない文字
+    Lex006,
+    /// 未定義のエスケープシーケンス
+    Lex007,
+    /// 指数の数字がない数値
+    Lex008,
+    /// 数値の区切り文字の位置が不正
+    Lex009,
+    /// 識別子、文字列リテラルが長すぎる
+    Lex010,
+    /// 文字列の埋め込み式が閉じられていない
+    Lex011,
+    /// 文字列の埋め込み式が空
+    Lex012,
 
     /// 構文エラー
     Parse001,
@@ -26,6 +46,20 @@ pub enum ErrorCode {
     Parse005,
     /// 特定ブロック内でのみ使えるキーワード
     Parse006,
+    /// 循環インポート
+    Parse007,
+    /// スコープ内に存在しないラベル
+    Parse008,
+    /// `@todo`が残っている（`--fail-on-todo`）
+    Parse009,
+    /// 最後以外のパラメータに可変長引数を指定した
+    Parse010,
+    /// 関数外でのreturn
+    Parse011,
+    /// 比較演算子の連続
+    Parse012,
+    /// 型と初期値のどちらもない変数宣言
+    Parse013,
 
     /// 意味解析エラー
     Semantic001,
@@ -43,6 +77,66 @@ pub enum ErrorCode {
     Semantic007,
     /// 引数の個数の不一致
     Semantic008,
+    /// 論理演算子にbool型以外の値
+    Semantic009,
+    /// 戻り値の型が定義されていない関数での値の返却
+    Semantic010,
+    /// 戻り値の型の不一致
+    Semantic011,
+    /// 戻り値を返さない関数
+    Semantic012,
+    /// 初期化前の変数の呼び出し
+    Semantic013,
+    /// 配列の要素の型の不一致
+    Semantic014,
+    /// 配列以外へのインデックスアクセス
+    Semantic015,
+    /// int型以外のインデックス
+    Semantic016,
+    /// 空の配列リテラル
+    Semantic017,
+    /// 異なる型同士の等値比較
+    Semantic018,
+    /// 定義されていない名前空間
+    Semantic019,
+    /// 名前空間に定義されていない関数、コルーチン
+    Semantic020,
+    /// 関数の重複定義
+    Semantic021,
+    /// yieldする値の型が定義されていないコルーチンでの値のyield
+    Semantic022,
+    /// yieldする値の型の不一致
+    Semantic023,
+    /// タスクではないstep関数の引数
+    Semantic024,
+    /// コルーチン以外でのyield
+    Semantic025,
+    /// 配列以外の型への分割代入
+    Semantic026,
+    /// 分割代入する変数の個数と配列リテラルの長さの不一致
+    Semantic027,
+    /// constで宣言した変数への再代入
+    Semantic028,
+    /// 初期値のないconst宣言
+    Semantic029,
+    /// @memoizeを指定できない関数
+    Semantic030,
+    /// 文字列に連結できない型
+    Semantic031,
+    /// 条件式の条件がboolでない
+    Semantic032,
+    /// 条件式の2つの値の型が異なる
+    Semantic033,
+    /// 組み込み関数と同名の関数定義
+    Semantic034,
+    /// コルーチンの重複定義
+    Semantic035,
+    /// 関数の引数名の重複
+    Semantic036,
+    /// 実行を開始する関数（main）がない
+    Semantic037,
+    /// 初期値から変数の型を推論できない
+    Semantic038,
     
     /// 実行時エラー
     Runtime001,
@@ -83,6 +177,59 @@ pub enum ErrorCode {
     Runtime019,
     /// 完了したタスクの呼び出し
     Runtime020,
+    /// 標準入力の読み込み失敗
+    Runtime021,
+    /// 配列の範囲外アクセス
+    Runtime022,
+    /// 出力サイズの上限超過
+    Runtime023,
+    /// 異なる型同士の等値比較
+    Runtime024,
+    /// 関数呼び出し前後のスコープの深さの不一致
+    Runtime025,
+    /// to_string関数の未対応の書式
+    Runtime026,
+    /// 組み込み関数の引数の個数の不正
+    Runtime027,
+    /// 負の数の平方根
+    Runtime028,
+    /// 値をyieldしなかったタスクの値の取得
+    Runtime029,
+    /// 型変換できない値
+    Runtime030,
+    /// 分割代入する変数の個数と配列の長さの不一致
+    Runtime031,
+    /// 文字列の範囲外アクセス
+    Runtime032,
+    /// 実行中のタスクの再開
+    Runtime033,
+    /// 値を返さなかった関数の呼び出し結果の使用
+    Runtime034,
+    /// 関数呼び出しのネストが上限を超えた
+    Runtime035,
+    /// 条件式の条件がboolでない
+    Runtime036,
+    /// assertの条件がfalse
+    Runtime037,
+    /// assert_eqの2つの値が等しくない
+    Runtime038,
+    /// format関数のプレースホルダーと引数の個数が一致しない
+    Runtime039,
+    /// 戻り値の型が関数の定義と一致しない
+    Runtime040,
+    /// 戻り値の型が定義されていない関数が値を返した
+    Runtime041,
+    /// intの絶対値が範囲外（最小値の絶対値）
+    Runtime042,
+    /// 実行用のスレッドを作成できない
+    Runtime043,
+
+    /// 使われていない変数
+    Warning001,
+    /// 到達しないコード
+    Warning002,
+    /// if文の両方の分岐が同じ
+    Warning003,
 
     ALL,
 }
@@ -91,17 +238,34 @@ impl ErrorCode {
     pub fn to_string(&self) -> String {
         let str = match self {
             Self::Io001 => "IO001",
+            Self::Io002 => "IO002",
+            Self::Io003 => "IO003",
+            Self::Io004 => "IO004",
             Self::Lex001 => "LEX001",
             Self::Lex002 => "LEX002",
             Self::Lex003 => "LEX003",
             Self::Lex004 => "LEX004",
             Self::Lex005 => "LEX005",
+            Self::Lex006 => "LEX006",
+            Self::Lex007 => "LEX007",
+            Self::Lex008 => "LEX008",
+            Self::Lex009 => "LEX009",
+            Self::Lex010 => "LEX010",
+            Self::Lex011 => "LEX011",
+            Self::Lex012 => "LEX012",
             Self::Parse001 => "PARSE001",
             Self::Parse002 => "PARSE002",
             Self::Parse003 => "PARSE003",
             Self::Parse004 => "PARSE004",
             Self::Parse005 => "PARSE005",
             Self::Parse006 => "PARSE006",
+            Self::Parse007 => "PARSE007",
+            Self::Parse008 => "PARSE008",
+            Self::Parse009 => "PARSE009",
+            Self::Parse010 => "PARSE010",
+            Self::Parse011 => "PARSE011",
+            Self::Parse012 => "PARSE012",
+            Self::Parse013 => "PARSE013",
             Self::Semantic001 => "SEMANTIC001",
             Self::Semantic002 => "SEMANTIC002",
             Self::Semantic003 => "SEMANTIC003",
@@ -110,6 +274,36 @@ impl ErrorCode {
             Self::Semantic006 => "SEMANTIC006",
             Self::Semantic007 => "SEMANTIC007",
             Self::Semantic008 => "SEMANTIC008",
+            Self::Semantic009 => "SEMANTIC009",
+            Self::Semantic010 => "SEMANTIC010",
+            Self::Semantic011 => "SEMANTIC011",
+            Self::Semantic012 => "SEMANTIC012",
+            Self::Semantic013 => "SEMANTIC013",
+            Self::Semantic014 => "SEMANTIC014",
+            Self::Semantic015 => "SEMANTIC015",
+            Self::Semantic016 => "SEMANTIC016",
+            Self::Semantic017 => "SEMANTIC017",
+            Self::Semantic018 => "SEMANTIC018",
+            Self::Semantic019 => "SEMANTIC019",
+            Self::Semantic020 => "SEMANTIC020",
+            Self::Semantic021 => "SEMANTIC021",
+            Self::Semantic022 => "SEMANTIC022",
+            Self::Semantic023 => "SEMANTIC023",
+            Self::Semantic024 => "SEMANTIC024",
+            Self::Semantic025 => "SEMANTIC025",
+            Self::Semantic026 => "SEMANTIC026",
+            Self::Semantic027 => "SEMANTIC027",
+            Self::Semantic028 => "SEMANTIC028",
+            Self::Semantic029 => "SEMANTIC029",
+            Self::Semantic030 => "SEMANTIC030",
+            Self::Semantic031 => "SEMANTIC031",
+            Self::Semantic032 => "SEMANTIC032",
+            Self::Semantic033 => "SEMANTIC033",
+            Self::Semantic034 => "SEMANTIC034",
+            Self::Semantic035 => "SEMANTIC035",
+            Self::Semantic036 => "SEMANTIC036",
+            Self::Semantic037 => "SEMANTIC037",
+            Self::Semantic038 => "SEMANTIC038",
             Self::Runtime001 => "RUNTIME001",
             Self::Runtime002 => "RUNTIME002",
             Self::Runtime003 => "RUNTIME003",
@@ -130,6 +324,32 @@ impl ErrorCode {
             Self::Runtime018 => "RUNTIME018",
             Self::Runtime019 => "RUNTIME019",
             Self::Runtime020 => "RUNTIME020",
+            Self::Runtime021 => "RUNTIME021",
+            Self::Runtime022 => "RUNTIME022",
+            Self::Runtime023 => "RUNTIME023",
+            Self::Runtime024 => "RUNTIME024",
+            Self::Runtime025 => "RUNTIME025",
+            Self::Runtime026 => "RUNTIME026",
+            Self::Runtime027 => "RUNTIME027",
+            Self::Runtime028 => "RUNTIME028",
+            Self::Runtime029 => "RUNTIME029",
+            Self::Runtime030 => "RUNTIME030",
+            Self::Runtime031 => "RUNTIME031",
+            Self::Runtime032 => "RUNTIME032",
+            Self::Runtime033 => "RUNTIME033",
+            Self::Runtime034 => "RUNTIME034",
+            Self::Runtime035 => "RUNTIME035",
+            Self::Runtime036 => "RUNTIME036",
+            Self::Runtime037 => "RUNTIME037",
+            Self::Runtime038 => "RUNTIME038",
+            Self::Runtime039 => "RUNTIME039",
+            Self::Runtime040 => "RUNTIME040",
+            Self::Runtime041 => "RUNTIME041",
+            Self::Runtime042 => "RUNTIME042",
+            Self::Runtime043 => "RUNTIME043",
+            Self::Warning001 => "WARNING001",
+            Self::Warning002 => "WARNING002",
+            Self::Warning003 => "WARNING003",
             Self::ALL => "ALL",
         };
         str.to_string()
@@ -27,6 +27,13 @@ impl ErrorMessage {
                 message = message.replace(&format!("{{{}}}", key), value);
             }
 
+            // 位置を含まないメッセージには、位置が分かる場合に末尾へ付与する
+            if !template.contains("{row}") {
+                if let (Some(row), Some(col)) = (&error.row, &error.col) {
+                    message.push_str(&format!("\n {}行 {}列目", row, col));
+                }
+            }
+
             if let Some(row) = &error.row {
                 message = message.replace(&"{row}", &row.to_string())
             }
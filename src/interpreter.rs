@@ -1,2 +1,3 @@
 pub mod execute;
 pub mod variable;
+pub mod output;
\ No newline at end of file
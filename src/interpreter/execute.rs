@@ -1,6 +1,7 @@
-use super::variable::VariableManager;
+use std::{collections::HashMap, io::Write};
+use super::{output::{LineEnding, Output}, variable::VariableManager};
 use crate::{
-    analyzer::{semantic::Semantic, task_table::TaskStatus}, common::{
+    analyzer::{function_table::FunctionInfo, semantic::Semantic, task_table::{CoroutineTask, FrameKind, TaskFrame, TaskStatus}}, common::{
         operator::{ Arithmetic, BinaryLogical, Comparison, Logical, UnaryLogical},
         types::{GreenValue, LiteralValue, Type},
     }, error::{
@@ -11,25 +12,77 @@ use crate::{
 #[derive(Debug)]
 pub enum EvalFlow<T> {
     Normal,
-    Continue,
-    Break,
+    /// continue（対象のループのラベル）
+    Continue(Option<String>),
+    /// break（対象のループのラベル）
+    Break(Option<String>),
     Return(T),
 }
 
-struct Interpreter {
+/// タスクの文を1つ実行した結果
+enum TaskStep {
+    /// yield以外の文を実行した
+    Executed,
+    /// yieldで中断した（yieldされた値）
+    Yielded(Option<GreenValue>),
+    /// 実行する文が残っていない
+    Completed,
+}
+
+/// 実行時の設定
+#[derive(Debug, Clone)]
+pub struct ExecuteOption {
+    /// debug関数の出力を有効にするか
+    pub debug: bool,
+    /// print関数で出力できるバイト数の上限
+    pub max_output_bytes: Option<usize>,
+    /// print関数が出力する改行文字
+    pub line_ending: LineEnding,
+    /// 関数呼び出しのネストの上限
+    pub max_call_depth: usize,
+    /// 出力する浮動小数点数の小数点以下の桁数（Noneの場合は値をそのまま出力する）
+    pub float_precision: Option<usize>,
+}
+
+/// 状態を保持したまま文を実行するインタプリタ
+pub struct Interpreter {
     variable_manager: VariableManager,
     manager: Semantic,
+    option: ExecuteOption,
+    output: Output,
+    /// `@memoize`を指定した関数の戻り値（関数名と引数をキーにする）
+    memo: HashMap<String, GreenValue>,
+    /// 実行中の関数呼び出しのネストの深さ
+    call_depth: usize,
+    /// `assert`、`assert_eq`が失敗したか
+    assertion_failed: bool,
 }
 
 impl Interpreter {
-    fn new(semantic: &Semantic) -> Self {
+    pub fn new(semantic: &Semantic, option: &ExecuteOption) -> Self {
+        Self::with_writer(semantic, option, Box::new(std::io::stdout()))
+    }
+
+    /// print関数などの出力先を指定して作成する
+    pub fn with_writer(semantic: &Semantic, option: &ExecuteOption, writer: Box<dyn Write>) -> Self {
         Self {
             variable_manager: VariableManager::new(),
             manager: semantic.clone(),
+            option: option.clone(),
+            output: Output::with_writer(option.max_output_bytes, option.line_ending, writer),
+            memo: HashMap::new(),
+            call_depth: 0,
+            assertion_failed: false,
         }
     }
 
-    fn execute_program(&mut self) -> Result<(), String> {
+    /// 実行時エラーの原因が`assert`、`assert_eq`の失敗であるか
+    pub fn assertion_failed(&self) -> bool {
+        self.assertion_failed
+    }
+
+    /// main関数の実行
+    pub fn execute_program(&mut self) -> Result<(), String> {
         if let Some(function_info) = self.manager.function_table.get_function_info("main") {
             self.execute(&function_info.process)?;
         } else {
@@ -45,12 +98,44 @@ impl Interpreter {
         Ok(())
     }
 
+    /// 現在参照できる変数の一覧（REPL用）
+    pub fn variables(&self) -> Vec<(String, GreenValue)> {
+        self.variable_manager.variables()
+    }
+
+    /// 意味解析の結果を差し替える（REPL用）
+    /// 
+    /// 既に存在するタスクは実行状態を引き継ぐ
+    pub fn update_semantic(&mut self, semantic: &Semantic) {
+        let mut task_table = semantic.task_table.clone();
+        for (task_name, task) in &self.manager.task_table.table {
+            task_table.set_task(task_name, task.clone());
+        }
+        self.manager = semantic.clone();
+        self.manager.task_table = task_table;
+    }
+
+    /// 文を1つ実行する（REPL用）
+    /// 
+    /// ## Return
+    /// 
+    /// - 式文の場合は評価結果
+    pub fn execute_statement(&mut self, node: &PrivateNode) -> Result<Option<GreenValue>, String> {
+        match node {
+            PrivateNode::ExpressionStatement { expression } => Ok(Some(self.evaluate_assignable(expression)?)),
+            _ => {
+                self.statement(node)?;
+                Ok(None)
+            },
+        }
+    }
+
     /// プログラムの実行
     fn execute(&mut self, block: &BlockNode) -> Result<EvalFlow<GreenValue>, String> {
         for child in block.statements.clone() {
             let result = self.statement(&child)?;
             match result {
-                EvalFlow::Return(_) | EvalFlow::Break | EvalFlow::Continue => return Ok(result),
+                EvalFlow::Return(_) | EvalFlow::Break(_) | EvalFlow::Continue(_) => return Ok(result),
                 EvalFlow::Normal => {},
             }
         }
@@ -58,32 +143,78 @@ impl Interpreter {
         Ok(EvalFlow::Normal)
     }
 
+    /// if文、ループのブロックの実行
+    /// 
+    /// ブロック内で宣言された変数は、ブロックを抜けると破棄する
+    fn execute_scoped(&mut self, block: &BlockNode) -> Result<EvalFlow<GreenValue>, String> {
+        self.variable_manager.push_scope();
+        let result = self.execute(block);
+        self.variable_manager.pop_scope();
+        result
+    }
+
     fn statement(&mut self, node: &PrivateNode) -> Result<EvalFlow<GreenValue>, String> {
         match &node {
-            PrivateNode::FunctionCall { name: _, arguments: _, return_flg:_ } => {
+            PrivateNode::FunctionCall { name: _, arguments: _, return_flg:_, .. } => {
                 self.execute_function(node)?;
             },
-            PrivateNode::VariableDeclaration { name, variable_type, initializer, doc: _ } => {
-                let value = match initializer {
-                    Some(expression) => self.evaluate_assignable(expression)?.value,
-                    None => LiteralValue::Null,
-                };
-
-                let value = GreenValue {
-                    value_type: variable_type.clone(),
-                    value,
+            PrivateNode::VariableDeclaration { name, variable_type, initializer, doc: _, .. } => {
+                let value = match (initializer, variable_type) {
+                    // 意味解析でfloat型の初期化を許可した整数リテラルを昇格する
+                    (Some(expression), Some(Type::Float)) => match self.evaluate_assignable(expression)?.value {
+                        LiteralValue::Int(i) => GreenValue::new(Type::Float, LiteralValue::Float(i as f64)),
+                        value => GreenValue::new(Type::Float, value),
+                    },
+                    (Some(expression), Some(variable_type)) => GreenValue::new(variable_type.clone(), self.evaluate_assignable(expression)?.value),
+                    // 型を省略した宣言は初期値の型を使う
+                    (Some(expression), None) => self.evaluate_assignable(expression)?,
+                    (None, Some(variable_type)) => GreenValue::new(variable_type.clone(), LiteralValue::Null),
+                    // 型も初期値もない宣言は構文解析でエラーになる
+                    (None, None) => GreenValue::new(Type::Bool, LiteralValue::Null),
                 };
                 self.variable_manager.set_variable(name, &value);
             },
-            PrivateNode::VariableAssignment { name, expression } => {
+            PrivateNode::ArrayDestructuring { names, variable_type, initializer, .. } => {
+                let value = self.evaluate_assignable(initializer)?;
+                let (Type::Array(element_type), LiteralValue::Array(elements)) = (variable_type, &value.value) else {
+                    return Err(ErrorMessage::global().get_error_message(
+                        ErrorContext::new(
+                            ErrorCode::Runtime010,
+                            None, None,
+                            vec![
+                                ("variable_type", &variable_type.to_string()),
+                                ("value_type", &value.value_type.to_string()),
+                                ("name", &names.join(", ")),
+                            ],
+                        )
+                    )?)
+                };
+                if elements.len() != names.len() {
+                    return Err(ErrorMessage::global().get_error_message(
+                        ErrorContext::new(
+                            ErrorCode::Runtime031,
+                            None, None,
+                            vec![
+                                ("variables", &names.len().to_string()),
+                                ("length", &elements.len().to_string()),
+                            ],
+                        )
+                    )?)
+                }
+                for (name, element) in names.iter().zip(elements) {
+                    let value = GreenValue::new((**element_type).clone(), element.clone());
+                    self.variable_manager.set_variable(name, &value);
+                }
+            },
+            PrivateNode::VariableAssignment { name, expression, .. } => {
                 let value = self.evaluate_assignable(expression)?;
                 self.variable_manager.change_variable(name.to_string(), value)?;
             },
             PrivateNode::IfStatement { condition_node, then_block, else_block } => {
                 return self.evaluate_if_statement(condition_node, then_block, else_block);
             },
-            PrivateNode::LoopStatement { condition_node, block } => {
-                return self.evaluate_loop_statement(condition_node, block);
+            PrivateNode::LoopStatement { label, condition_node, block, else_block } => {
+                return self.evaluate_loop_statement(label, condition_node, block, else_block);
             },
             
             PrivateNode::ReturnStatement { assignalbe } => {
@@ -91,65 +222,27 @@ impl Interpreter {
                 return Ok(EvalFlow::Return(return_value));
             },
 
-            PrivateNode::CoroutineInstantiation { task_name:_, coroutine_name:_ } => {
+            PrivateNode::CoroutineInstantiation { task_name:_, coroutine_name:_, .. } => {
                 // match self.manager.coroutine_table.add_task(task_name, coroutine_name) {
                 //     Ok(_) => {},
                 //     Err(e) => return Err(ErrorMessage::global().get_error_message(e)?),
                 // }
             },
             PrivateNode::CoroutineResume { task_name } => {
-                let mut task = match self.manager.task_table.get_task(task_name) {
-                    Some(task) => task,
-                    None => panic!("見つからない"),
-                };
-
-                match &task.status {
-                    TaskStatus::Completed => {
-                        return Err(ErrorMessage::global().get_error_message(
-                            ErrorContext::new(
-                                ErrorCode::Runtime020,
-                                None, None,
-                                vec![("coroutine_name", task_name)],
-                            )
-                        )?)
-                    },
-                    TaskStatus::Ready | TaskStatus::Paused => {
-                        task.status = TaskStatus::Running;
-                    },
-                    TaskStatus::Running => {
-                        panic!("今のところ存在しないエラー")
-                    },
-                }
+                self.resume_task(task_name)?;
+            },
 
-                loop {
-                    if matches!(task.status, TaskStatus::Completed) {
-                        self.manager.task_table.set_task(task_name, task);
-                        break;
-                    }
-                    let index = task.current_position;
-                    let node = &task.process[index];
-                    match node {
-                        PrivateNode::Yield => {
-                            task.step();
-                            task.status = TaskStatus::Paused;
-                            self.manager.task_table.set_task(task_name, task);
-                            break;
-                        },
-                        _ => {
-                            self.statement(node)?;
-                            task.step();                            
-                        },
-                    }
-                }
+            PrivateNode::ExpressionStatement { expression } => {
+                self.evaluate_assignable(expression)?;
             },
 
             PrivateNode::ProcessComment { comment:_ } => {},
 
-            PrivateNode::Break => {
-                return Ok(EvalFlow::Break);
+            PrivateNode::Break { label } => {
+                return Ok(EvalFlow::Break(label.clone()));
             },
-            PrivateNode::Continue => {
-                return Ok(EvalFlow::Continue);
+            PrivateNode::Continue { label } => {
+                return Ok(EvalFlow::Continue(label.clone()));
             }
             _ => return Err(ErrorMessage::global().get_error_message(
                 ErrorContext::new(
@@ -162,61 +255,624 @@ impl Interpreter {
         Ok(EvalFlow::Normal)
     }
 
-    /// print関数の実行
+    /// タスクを次のyieldまたは終了まで実行する
+    /// 
+    /// ## Return
+    /// 
+    /// - yieldされた値（値のないyieldまたは終了の場合は`None`）
+    fn resume_task(&mut self, task_name: &str) -> Result<Option<GreenValue>, String> {
+        let mut task = self.start_task(task_name)?;
+        loop {
+            match self.step_task(&mut task)? {
+                TaskStep::Executed => {},
+                TaskStep::Yielded(value) => {
+                    task.status = TaskStatus::Paused;
+                    self.manager.task_table.set_task(task_name, task);
+                    return Ok(value)
+                },
+                TaskStep::Completed => {
+                    task.status = TaskStatus::Completed;
+                    self.manager.task_table.set_task(task_name, task);
+                    return Ok(None)
+                },
+            }
+        }
+    }
+
+    /// typeof関数の実行（引数の実行時の型名を返す）
+    fn typeof_function(&mut self, arguments: &Vec<PrivateNode>) -> Result<GreenValue, String> {
+        match self.evaluate_argument(arguments)?.as_slice() {
+            [value] => Ok(GreenValue::new(Type::String, LiteralValue::String(value.value_type.to_string()))),
+            _ => Err(ErrorMessage::global().get_error_message(
+                ErrorContext::new(
+                    ErrorCode::Runtime011,
+                    None, None,
+                    vec![("node", &format!("{:?}", arguments))],
+                )
+            )?),
+        }
+    }
+
+    /// format関数の実行
+    /// 
+    /// テンプレートの`{}`を引数の文字列表現で順に置き換える。`{{`、`}}`は`{`、`}`として出力する
+    fn format_function(&mut self, arguments: &Vec<PrivateNode>, row: u32, col: u32) -> Result<GreenValue, String> {
+        let values = self.evaluate_argument(arguments)?;
+        let Some((GreenValue { value: LiteralValue::String(template), .. }, values)) = values.split_first() else {
+            return Err(ErrorMessage::global().get_error_message(
+                ErrorContext::new(
+                    ErrorCode::Runtime011,
+                    None, None,
+                    vec![("node", &format!("{:?}", arguments))],
+                )
+            )?)
+        };
+
+        let mut result = String::new();
+        let mut values = values.iter();
+        let mut placeholders = 0;
+        let mut chars = template.chars().peekable();
+        while let Some(c) = chars.next() {
+            match (c, chars.peek()) {
+                ('{', Some('{')) | ('}', Some('}')) => {
+                    chars.next();
+                    result.push(c);
+                },
+                ('{', Some('}')) => {
+                    chars.next();
+                    placeholders += 1;
+                    if let Some(value) = values.next() {
+                        result.push_str(&value.value.to_string());
+                    }
+                },
+                _ => result.push(c),
+            }
+        }
+
+        if placeholders != arguments.len() - 1 {
+            return Err(ErrorMessage::global().get_error_message(
+                ErrorContext::new(
+                    ErrorCode::Runtime039,
+                    Some(row), Some(col),
+                    vec![
+                        ("placeholders", &placeholders.to_string()),
+                        ("arguments", &(arguments.len() - 1).to_string()),
+                    ],
+                )
+            )?)
+        }
+        Ok(GreenValue::new(Type::String, LiteralValue::String(result)))
+    }
+
+    /// assert、assert_eq関数の実行
+    /// 
+    /// 条件がfalseの場合、または2つの値が等しくない場合にエラーにする
+    fn assert_function(&mut self, name: &str, arguments: &Vec<PrivateNode>, row: u32, col: u32) -> Result<(), String> {
+        let error = match (name, self.evaluate_argument(arguments)?.as_slice()) {
+            ("assert", [GreenValue { value: LiteralValue::Bool(true), .. }]) => return Ok(()),
+            ("assert", [_]) => ErrorContext::new(ErrorCode::Runtime037, Some(row), Some(col), vec![]),
+            ("assert_eq", [left, right]) if left.value == right.value => return Ok(()),
+            ("assert_eq", [left, right]) => ErrorContext::new(
+                ErrorCode::Runtime038,
+                Some(row), Some(col),
+                vec![
+                    ("left", &left.value.to_string()),
+                    ("right", &right.value.to_string()),
+                ],
+            ),
+            _ => return Err(ErrorMessage::global().get_error_message(
+                ErrorContext::new(
+                    ErrorCode::Runtime011,
+                    None, None,
+                    vec![("node", &format!("{:?}", arguments))],
+                )
+            )?),
+        };
+        self.assertion_failed = true;
+        Err(ErrorMessage::global().get_error_message(error)?)
+    }
+
+    /// step関数の実行
+    /// 
+    /// タスクの文を1つだけ実行し、実行後の状態を文字列で返す
+    /// 
+    /// - `"running"` - 文を実行した
+    /// - `"paused"` - yieldで中断した
+    /// - `"completed"` - 実行する文が残っていなかった
+    fn step_function(&mut self, arguments: &Vec<PrivateNode>) -> Result<GreenValue, String> {
+        let task_name = match arguments.as_slice() {
+            [PrivateNode::Variable { name, .. }] => name,
+            _ => return Err(ErrorMessage::global().get_error_message(
+                ErrorContext::new(
+                    ErrorCode::Runtime011,
+                    None, None,
+                    vec![("node", &format!("{:?}", arguments))],
+                )
+            )?),
+        };
+
+        let mut task = self.start_task(task_name)?;
+        let status = match self.step_task(&mut task)? {
+            TaskStep::Executed => {
+                task.status = TaskStatus::Paused;
+                "running"
+            },
+            TaskStep::Yielded(_) => {
+                task.status = TaskStatus::Paused;
+                "paused"
+            },
+            TaskStep::Completed => {
+                task.status = TaskStatus::Completed;
+                "completed"
+            },
+        };
+        self.manager.task_table.set_task(task_name, task);
+        Ok(GreenValue::new(Type::String, LiteralValue::String(status.to_string())))
+    }
+
+    /// タスクを取得し、実行中にする
+    /// 
+    /// 実行中の状態はタスク表にも記録し、コルーチンから自身（または呼び出し元）を再開する循環を検出する
+    fn start_task(&mut self, task_name: &str) -> Result<CoroutineTask, String> {
+        let mut task = match self.manager.task_table.get_task(task_name) {
+            Some(task) => task,
+            None => panic!("見つからない"),
+        };
+
+        match &task.status {
+            TaskStatus::Completed => {
+                return Err(ErrorMessage::global().get_error_message(
+                    ErrorContext::new(
+                        ErrorCode::Runtime020,
+                        None, None,
+                        vec![("coroutine_name", task_name)],
+                    )
+                )?)
+            },
+            TaskStatus::Ready | TaskStatus::Paused => {
+                task.status = TaskStatus::Running;
+            },
+            TaskStatus::Running => {
+                return Err(ErrorMessage::global().get_error_message(
+                    ErrorContext::new(
+                        ErrorCode::Runtime033,
+                        None, None,
+                        vec![("task_name", task_name)],
+                    )
+                )?)
+            },
+        }
+        self.manager.task_table.set_task(task_name, task.clone());
+        Ok(task)
+    }
+
+    /// タスクの文を1つ実行する
+    /// 
+    /// タスクのローカル変数は、実行中のみスコープに追加し、呼び出し元の変数と分けて保持する
+    fn step_task(&mut self, task: &mut CoroutineTask) -> Result<TaskStep, String> {
+        self.variable_manager.push_scope_with(std::mem::take(&mut task.variables));
+        let result = self.step_task_frames(task);
+        task.variables = self.variable_manager.take_scope();
+        result
+    }
+
+    /// 実行位置のブロックから文を1つ実行する
+    /// 
+    /// ブロックの出入りとループ条件の再評価は、文の実行に含めない
+    fn step_task_frames(&mut self, task: &mut CoroutineTask) -> Result<TaskStep, String> {
+        loop {
+            let Some(frame) = task.frames.last_mut() else {
+                return Ok(TaskStep::Completed)
+            };
+
+            // ブロックの末尾に達した場合、ループであれば条件を再評価する
+            if frame.position >= frame.statements.len() {
+                match frame.kind.clone() {
+                    FrameKind::Loop { label:_, condition, else_block } => {
+                        if self.evaluate_condition(&condition, ErrorCode::Runtime017)? {
+                            if let Some(frame) = task.frames.last_mut() {
+                                frame.position = 0;
+                            }
+                        } else {
+                            // 条件が偽になって終了した場合は、ループのブロックをelse節に置き換える
+                            task.frames.pop();
+                            if let Some(else_block) = else_block {
+                                task.frames.push(TaskFrame::new(FrameKind::Block, &else_block));
+                            }
+                        }
+                    },
+                    FrameKind::Block => { task.frames.pop(); },
+                }
+                continue;
+            }
+
+            let node = frame.statements[frame.position].clone();
+            frame.position += 1;
+            match node {
+                PrivateNode::Yield { value } => {
+                    let value = match value {
+                        Some(value) => Some(self.evaluate_assignable(&value)?),
+                        None => None,
+                    };
+                    return Ok(TaskStep::Yielded(value))
+                },
+                PrivateNode::IfStatement { condition_node, then_block, else_block } => {
+                    if self.evaluate_condition(&condition_node, ErrorCode::Runtime014)? {
+                        task.frames.push(TaskFrame::new(FrameKind::Block, &then_block));
+                    } else if let Some(else_block) = else_block {
+                        task.frames.push(TaskFrame::new(FrameKind::Block, &else_block));
+                    }
+                },
+                PrivateNode::LoopStatement { label, condition_node, block, else_block } => {
+                    if self.evaluate_condition(&condition_node, ErrorCode::Runtime017)? {
+                        let kind = FrameKind::Loop { label, condition: *condition_node, else_block };
+                        task.frames.push(TaskFrame::new(kind, &block));
+                    } else if let Some(else_block) = else_block {
+                        task.frames.push(TaskFrame::new(FrameKind::Block, &else_block));
+                    }
+                },
+                PrivateNode::Break { label } => {
+                    // 対象のループのブロックまで取り除く
+                    while let Some(frame) = task.frames.pop() {
+                        if let FrameKind::Loop { label: loop_label, .. } = frame.kind {
+                            if label.is_none() || label == loop_label { break; }
+                        }
+                    }
+                },
+                PrivateNode::Continue { label } => {
+                    // 対象のループのブロックを末尾まで進め、条件を再評価させる
+                    while let Some(frame) = task.frames.last_mut() {
+                        if let FrameKind::Loop { label: loop_label, .. } = &frame.kind {
+                            if label.is_none() || label == *loop_label {
+                                frame.position = frame.statements.len();
+                                break;
+                            }
+                        }
+                        task.frames.pop();
+                    }
+                },
+                _ => {
+                    self.statement(&node)?;
+                },
+            }
+            return Ok(TaskStep::Executed)
+        }
+    }
+
+    /// 条件式の評価
+    /// 
+    /// bool型以外の場合は`error_code`のエラーを返す
+    fn evaluate_condition(&mut self, condition_node: &PrivateNode, error_code: ErrorCode) -> Result<bool, String> {
+        match self.evaluate_assignable(condition_node)?.value {
+            LiteralValue::Bool(result) => Ok(result),
+            _ => Err(ErrorMessage::global().get_error_message(
+                ErrorContext::new(
+                    error_code,
+                    None, None,
+                    vec![("node", &format!("{:?}", condition_node))],
+                )
+            )?),
+        }
+    }
+
+    /// print関数（println関数）の実行
+    /// 
+    /// 引数を空白区切りで出力して改行する。引数がない場合は改行のみ出力する
     fn print_function(&mut self, arguments: &Vec<PrivateNode>) -> Result<(), String> {
+        if arguments.is_empty() {
+            return self.output.write_line("")
+        }
+        let values = self.evaluate_argument(arguments)?;
+        let result = values.iter().map(|x| x.value.to_output_string(self.option.float_precision)).collect::<Vec<_>>().join(" ");
+        self.output.write_line(&result)?;
+        Ok(())
+    }
+
+    /// print_raw関数の実行
+    /// 
+    /// 引数を区切らずに連結し、改行せずに出力する
+    fn print_raw_function(&mut self, arguments: &Vec<PrivateNode>) -> Result<(), String> {
+        let values = self.evaluate_argument(arguments)?;
+        let result = values.iter().map(|x| x.value.to_output_string(self.option.float_precision)).collect::<String>();
+        self.output.write(&result)?;
+        self.output.flush()
+    }
+
+    /// debug関数の実行
+    /// 
+    /// `--debug`指定時のみ標準エラー出力へ出力する
+    fn debug_function(&mut self, arguments: &Vec<PrivateNode>) -> Result<(), String> {
+        if !self.option.debug {
+            return Ok(())
+        }
         let values = self.evaluate_argument(arguments)?;
-        let result = values.iter().map(|x| x.value.to_string()).collect::<Vec<_>>().join(" ");
-        println!("{}", result);
+        let result = values.iter().map(|x| x.value.to_output_string(self.option.float_precision)).collect::<Vec<_>>().join(" ");
+        eprintln!("{}", result);
         Ok(())
     }
 
+    /// input関数の実行
+    /// 
+    /// 標準入力から1行読み込み、末尾の改行を取り除いて返す  
+    /// EOFに達した場合は空文字列を返す
+    fn input_function(&mut self) -> Result<GreenValue, String> {
+        let mut line = String::new();
+        if let Err(e) = std::io::stdin().read_line(&mut line) {
+            return Err(ErrorMessage::global().get_error_message(
+                ErrorContext::new(
+                    ErrorCode::Runtime021,
+                    None, None,
+                    vec![("message", &e.to_string())],
+                )
+            )?)
+        }
+        let line = line.trim_end_matches(['\n', '\r']).to_string();
+        Ok(GreenValue::new(Type::String, LiteralValue::String(line)))
+    }
+
+    /// to_string関数の実行
+    /// 
+    /// 第2引数でint型の表記（"dec", "hex", "bin", "oct"）を指定できる
+    fn stringify_function(&mut self, name: &str, arguments: &Vec<PrivateNode>) -> Result<GreenValue, String> {
+        let values = self.evaluate_argument(arguments)?;
+        let text = match values.as_slice() {
+            [value] => value.value.to_string(),
+            [value, format] => {
+                let format = format.value.to_string();
+                match (&value.value, format.as_str()) {
+                    (LiteralValue::Int(i), "dec") => i.to_string(),
+                    (LiteralValue::Int(i), "hex" | "bin" | "oct") => {
+                        let sign = if *i < 0 { "-" } else { "" };
+                        let digits = match format.as_str() {
+                            "hex" => format!("{:x}", i.unsigned_abs()),
+                            "bin" => format!("{:b}", i.unsigned_abs()),
+                            _ => format!("{:o}", i.unsigned_abs()),
+                        };
+                        format!("{}{}", sign, digits)
+                    },
+                    _ => return Err(ErrorMessage::global().get_error_message(
+                        ErrorContext::new(
+                            ErrorCode::Runtime026,
+                            None, None,
+                            vec![
+                                ("value_type", &value.value_type.to_string()),
+                                ("format", &format),
+                            ],
+                        )
+                    )?),
+                }
+            },
+            _ => return Err(ErrorMessage::global().get_error_message(
+                ErrorContext::new(
+                    ErrorCode::Runtime027,
+                    None, None,
+                    vec![
+                        ("function", name),
+                        ("argument", &values.len().to_string()),
+                    ],
+                )
+            )?),
+        };
+        Ok(GreenValue::new(Type::String, LiteralValue::String(text)))
+    }
+
+    /// 型変換関数（to_int, to_float）の実行
+    /// 
+    /// float型からint型への変換は小数点以下を切り捨てる
+    /// string型は数値として解釈できる場合のみ変換する
+    fn cast_function(&mut self, name: &str, arguments: &Vec<PrivateNode>) -> Result<GreenValue, String> {
+        let values = self.evaluate_argument(arguments)?;
+        let [value] = values.as_slice() else {
+            return Err(ErrorMessage::global().get_error_message(
+                ErrorContext::new(
+                    ErrorCode::Runtime027,
+                    None, None,
+                    vec![
+                        ("function", name),
+                        ("argument", &values.len().to_string()),
+                    ],
+                )
+            )?)
+        };
+
+        let result = match (name, &value.value) {
+            ("to_int", LiteralValue::Int(i)) => Some(LiteralValue::Int(*i)),
+            ("to_int", LiteralValue::Float(f)) => {
+                let truncated = f.trunc();
+                if truncated >= i32::MIN as f64 && truncated <= i32::MAX as f64 {
+                    Some(LiteralValue::Int(truncated as i32))
+                } else {
+                    None
+                }
+            },
+            ("to_int", LiteralValue::String(s)) => s.trim().parse::<i32>().ok().map(LiteralValue::Int),
+            ("to_float", LiteralValue::Int(i)) => Some(LiteralValue::Float(*i as f64)),
+            ("to_float", LiteralValue::Float(f)) => Some(LiteralValue::Float(*f)),
+            ("to_float", LiteralValue::String(s)) => s.trim().parse::<f64>().ok().map(LiteralValue::Float),
+            _ => None,
+        };
+
+        let value_type = if name == "to_int" { Type::Int } else { Type::Float };
+        match result {
+            Some(result) => Ok(GreenValue::new(value_type, result)),
+            None => Err(ErrorMessage::global().get_error_message(
+                ErrorContext::new(
+                    ErrorCode::Runtime030,
+                    None, None,
+                    vec![
+                        ("value", &value.value.to_string()),
+                        ("value_type", &value.value_type.to_string()),
+                        ("target_type", &value_type.to_string()),
+                    ],
+                )
+            )?),
+        }
+    }
+
+    /// 文字列関数（char_at, substring）の実行
+    /// 
+    /// インデックスはUnicodeスカラー値（`char`）単位で数え、substringの終了位置は含まない
+    fn string_function(&mut self, name: &str, arguments: &Vec<PrivateNode>) -> Result<GreenValue, String> {
+        let values = self.evaluate_argument(arguments)?;
+        let values = values.iter().map(|x| &x.value).collect::<Vec<_>>();
+        let (text, start, end) = match (name, values.as_slice()) {
+            ("char_at", [LiteralValue::String(s), LiteralValue::Int(i)]) => (s, *i, i.saturating_add(1)),
+            ("substring", [LiteralValue::String(s), LiteralValue::Int(start), LiteralValue::Int(end)]) => (s, *start, *end),
+            _ => return Err(ErrorMessage::global().get_error_message(
+                ErrorContext::new(
+                    ErrorCode::Runtime011,
+                    None, None,
+                    vec![("node", &format!("{:?}", values))],
+                )
+            )?),
+        };
+
+        let length = text.chars().count();
+        if start < 0 || end < start || end as usize > length {
+            return Err(ErrorMessage::global().get_error_message(
+                ErrorContext::new(
+                    ErrorCode::Runtime032,
+                    None, None,
+                    vec![
+                        ("function", name),
+                        ("length", &length.to_string()),
+                        ("start", &start.to_string()),
+                        ("end", &end.to_string()),
+                    ],
+                )
+            )?)
+        }
+        let result = text.chars().skip(start as usize).take((end - start) as usize).collect::<String>();
+        Ok(GreenValue::new(Type::String, LiteralValue::String(result)))
+    }
+
+    /// chars、from_chars関数の実行
+    /// 
+    /// 文字は1文字の文字列で表す。from_charsは要素を区切らずに連結する
+    fn chars_function(&mut self, name: &str, arguments: &Vec<PrivateNode>) -> Result<GreenValue, String> {
+        let values = self.evaluate_argument(arguments)?;
+        let values = values.iter().map(|x| &x.value).collect::<Vec<_>>();
+        match (name, values.as_slice()) {
+            ("chars", [LiteralValue::String(s)]) => {
+                let elements = s.chars().map(|c| LiteralValue::String(c.to_string())).collect();
+                Ok(GreenValue::new(Type::Array(Box::new(Type::String)), LiteralValue::Array(elements)))
+            },
+            ("from_chars", [LiteralValue::Array(elements)]) => {
+                let text = elements.iter().map(|x| x.to_string()).collect::<String>();
+                Ok(GreenValue::new(Type::String, LiteralValue::String(text)))
+            },
+            _ => Err(ErrorMessage::global().get_error_message(
+                ErrorContext::new(
+                    ErrorCode::Runtime011,
+                    None, None,
+                    vec![("node", &format!("{:?}", values))],
+                )
+            )?),
+        }
+    }
+
+    /// 数学関数（abs, sqrt, pow）の実行
+    /// 
+    /// absはint型の引数に対してint型を返す
+    fn math_function(&mut self, name: &str, arguments: &Vec<PrivateNode>) -> Result<GreenValue, String> {
+        let values = self.evaluate_argument(arguments)?;
+        let values = values.iter().map(|x| &x.value).collect::<Vec<_>>();
+        match (name, values.as_slice()) {
+            ("abs", [LiteralValue::Int(x)]) => {
+                let Some(abs) = x.checked_abs() else {
+                    return Err(ErrorMessage::global().get_error_message(
+                        ErrorContext::new(
+                            ErrorCode::Runtime042,
+                            None, None,
+                            vec![("value", &x.to_string())],
+                        )
+                    )?)
+                };
+                Ok(GreenValue::new(Type::Int, LiteralValue::Int(abs)))
+            },
+            ("abs", [LiteralValue::Float(x)]) => Ok(GreenValue::new(Type::Float, LiteralValue::Float(x.abs()))),
+            ("sqrt", [LiteralValue::Float(x)]) => {
+                if *x < 0.0 {
+                    return Err(ErrorMessage::global().get_error_message(
+                        ErrorContext::new(
+                            ErrorCode::Runtime028,
+                            None, None,
+                            vec![("value", &x.to_string())],
+                        )
+                    )?)
+                }
+                Ok(GreenValue::new(Type::Float, LiteralValue::Float(x.sqrt())))
+            },
+            ("pow", [LiteralValue::Float(base), LiteralValue::Float(exp)]) => {
+                Ok(GreenValue::new(Type::Float, LiteralValue::Float(base.powf(*exp))))
+            },
+            _ => Err(ErrorMessage::global().get_error_message(
+                ErrorContext::new(
+                    ErrorCode::Runtime027,
+                    None, None,
+                    vec![
+                        ("function", name),
+                        ("argument", &values.len().to_string()),
+                    ],
+                )
+            )?),
+        }
+    }
+
     fn execute_function(&mut self, node: &PrivateNode) -> Result<Option<GreenValue>, String> {
         match &node {
-            PrivateNode::FunctionCall { name, arguments, return_flg:_ } => {
+            PrivateNode::FunctionCall { name, arguments, return_flg:_, row, col } => {
                 match name.as_str() {
-                    "print" => self.print_function(arguments)?,
+                    "print" | "println" => self.print_function(arguments)?,
+                    "print_raw" => self.print_raw_function(arguments)?,
+                    "input" => return Ok(Some(self.input_function()?)),
+                    "debug" => self.debug_function(arguments)?,
+                    "to_string" => return Ok(Some(self.stringify_function(name, arguments)?)),
+                    "to_int" | "to_float" => return Ok(Some(self.cast_function(name, arguments)?)),
+                    "abs" | "sqrt" | "pow" => return Ok(Some(self.math_function(name, arguments)?)),
+                    "char_at" | "substring" => return Ok(Some(self.string_function(name, arguments)?)),
+                    "chars" | "from_chars" => return Ok(Some(self.chars_function(name, arguments)?)),
+                    "step" => return Ok(Some(self.step_function(arguments)?)),
+                    "typeof" => return Ok(Some(self.typeof_function(arguments)?)),
+                    "format" => return Ok(Some(self.format_function(arguments, *row, *col)?)),
+                    "assert" | "assert_eq" => self.assert_function(name, arguments, *row, *col)?,
                     _ => {
                         if let Some(function_info) = self.manager.function_table.get_function_info(name) {
-                            self.variable_manager.push_scope();
-
+                            // 引数は呼び出し元のスコープで評価する
                             let values = self.evaluate_argument(arguments)?;
-                            for (param, value) in function_info.parameters.into_iter().zip(values.into_iter()) {
-                                if param.variable_type == value.value_type {
-                                    self.variable_manager.set_variable(&param.name, &value);
-                                }
-                                else {
-                                    return Err(ErrorMessage::global().get_error_message(
-                                        ErrorContext::new(
-                                            ErrorCode::Runtime013,
-                                            None, None,
-                                            vec![
-                                                ("parameter", &param.variable_type.to_string()),
-                                                ("argument", &value.value_type.to_string()),
-                                                ("function_name", name),
-                                                ("param_name", &param.name),
-                                            ],
-                                        )
-                                    )?)
-                                }
+                            let memo_key = function_info.memoize.then(|| format!("{}{:?}", name, values));
+                            if let Some(value) = memo_key.as_ref().and_then(|key| self.memo.get(key)) {
+                                return Ok(Some(value.clone()))
                             }
 
-                            let result = self.execute(&function_info.process)?;
-                            match result {
-                                EvalFlow::Return(value) => {
-                                    self.variable_manager.pop_scope();
-                                    return Ok(Some(value))
-                                },
-                                EvalFlow::Normal => {},
-                                _ => {
-                                    return Err(ErrorMessage::global().get_error_message(
-                                        ErrorContext::new(
-                                            ErrorCode::Runtime018,
-                                            None, None,
-                                            vec![("node", &format!("{:?}", result))],
-                                        )
-                                    )?)
-                                }
+                            if self.call_depth >= self.option.max_call_depth {
+                                return Err(ErrorMessage::global().get_error_message(
+                                    ErrorContext::new(
+                                        ErrorCode::Runtime035,
+                                        None, None,
+                                        vec![
+                                            ("function_name", name),
+                                            ("max_call_depth", &self.option.max_call_depth.to_string()),
+                                        ],
+                                    )
+                                )?);
                             }
+
+                            // エラーの場合もスコープを戻すため、結果は関数の実行後に確認する
+                            let depth = self.variable_manager.depth();
+                            self.variable_manager.push_scope();
+                            self.call_depth += 1;
+                            let values = Self::pack_variadic_arguments(&function_info, values);
+                            let result = self.call_function(name, &function_info, values);
+                            self.call_depth -= 1;
+                            self.variable_manager.pop_scope();
+                            self.variable_manager.check_depth(depth, name)?;
+
+                            let value = result?;
+                            if let (Some(key), Some(value)) = (memo_key, &value) {
+                                self.memo.insert(key, value.clone());
+                            }
+                            return Ok(value)
                         } else {
                             return Err(ErrorMessage::global().get_error_message(
                                 ErrorContext::new(
@@ -226,8 +882,6 @@ impl Interpreter {
                                 )
                             )?);
                         }
-
-                        self.variable_manager.pop_scope();
                     },
                 }
                 Ok(None)
@@ -242,13 +896,79 @@ impl Interpreter {
         }
     }
 
+    /// 可変長引数の関数の場合、固定のパラメータに渡す分を除いた引数を1つの配列にまとめる
+    fn pack_variadic_arguments(function_info: &FunctionInfo, mut values: Vec<GreenValue>) -> Vec<GreenValue> {
+        let Some(param) = function_info.parameters.last().filter(|_| function_info.is_variadic) else { return values };
+        let rest = values.split_off(function_info.fixed_parameter_count().min(values.len()));
+        let elements = rest.into_iter().map(|value| value.value).collect();
+        values.push(GreenValue::new(param.variable_type.clone(), LiteralValue::Array(elements)));
+        values
+    }
+
+    /// 引数を設定し、関数の処理を実行する（スコープの追加と削除は呼び出し元で行う）
+    fn call_function(&mut self, name: &str, function_info: &FunctionInfo, values: Vec<GreenValue>) -> Result<Option<GreenValue>, String> {
+        for (param, value) in function_info.parameters.iter().zip(values.into_iter()) {
+            if param.variable_type == value.value_type {
+                self.variable_manager.set_variable(&param.name, &value);
+            }
+            else {
+                return Err(ErrorMessage::global().get_error_message(
+                    ErrorContext::new(
+                        ErrorCode::Runtime013,
+                        None, None,
+                        vec![
+                            ("parameter", &param.variable_type.to_string()),
+                            ("argument", &value.value_type.to_string()),
+                            ("function_name", name),
+                            ("param_name", &param.name),
+                        ],
+                    )
+                )?)
+            }
+        }
+
+        let result = self.execute(&function_info.process)?;
+        match result {
+            // 意味解析で検出できなかった場合に備え、戻り値の型を定義と照合する
+            EvalFlow::Return(value) => match &function_info.return_type {
+                Some(return_type) if *return_type == value.value_type => Ok(Some(value)),
+                Some(return_type) => Err(ErrorMessage::global().get_error_message(
+                    ErrorContext::new(
+                        ErrorCode::Runtime040,
+                        None, None,
+                        vec![
+                            ("expected", &return_type.to_string()),
+                            ("actual", &value.value_type.to_string()),
+                            ("function_name", name),
+                        ],
+                    )
+                )?),
+                None => Err(ErrorMessage::global().get_error_message(
+                    ErrorContext::new(
+                        ErrorCode::Runtime041,
+                        None, None,
+                        vec![("function_name", name)],
+                    )
+                )?),
+            },
+            EvalFlow::Normal => Ok(None),
+            _ => Err(ErrorMessage::global().get_error_message(
+                ErrorContext::new(
+                    ErrorCode::Runtime018,
+                    None, None,
+                    vec![("node", &format!("{:?}", result))],
+                )
+            )?),
+        }
+    }
+
     fn evaluate_if_statement(&mut self, condition_node: &PrivateNode, then_block: &BlockNode, else_block: &Option<BlockNode>) -> Result<EvalFlow<GreenValue>, String> {
         if let LiteralValue::Bool(condition_result) = self.evaluate_assignable(&condition_node)?.value {
             match condition_result {
-                true => return self.execute(then_block),
+                true => return self.execute_scoped(then_block),
                 false => {
                     if let Some(else_node) = else_block {
-                        return self.execute(else_node)
+                        return self.execute_scoped(else_node)
                     }
                 }
             }
@@ -264,17 +984,21 @@ impl Interpreter {
         Ok(EvalFlow::Normal)
     }
 
-    fn evaluate_loop_statement(&mut self, condition_node: &PrivateNode, block: &BlockNode) -> Result<EvalFlow<GreenValue>, String> {
+    /// ループの評価
+    /// 
+    /// ラベルが一致しないbreak, continueは外側のループへ伝播する  
+    /// else節は、breakせずに条件が偽になった場合のみ実行する
+    fn evaluate_loop_statement(&mut self, label: &Option<String>, condition_node: &PrivateNode, block: &BlockNode, else_block: &Option<BlockNode>) -> Result<EvalFlow<GreenValue>, String> {
         loop {
             let condition_value = self.evaluate_assignable(&condition_node)?;
 
             match condition_value.value {
                 LiteralValue::Bool(true) => {
-                    let result = self.execute(block)?;
+                    let result = self.execute_scoped(block)?;
                     match result {
-                        EvalFlow::Break => break,
-                        EvalFlow::Continue => continue,
-                        EvalFlow::Return(_) => return Ok(result),
+                        EvalFlow::Break(target) if target.is_none() || target == *label => return Ok(EvalFlow::Normal),
+                        EvalFlow::Continue(target) if target.is_none() || target == *label => continue,
+                        EvalFlow::Break(_) | EvalFlow::Continue(_) | EvalFlow::Return(_) => return Ok(result),
                         EvalFlow::Normal => {},
                     }
                 },
@@ -288,7 +1012,10 @@ impl Interpreter {
                 )?)
             }
         }
-        Ok(EvalFlow::Normal)
+        match else_block {
+            Some(else_block) => self.execute_scoped(else_block),
+            None => Ok(EvalFlow::Normal),
+        }
     }
 
     /// 引数の評価
@@ -304,14 +1031,47 @@ impl Interpreter {
     /// LiteralValueからGreenValueへの変換も行う
     fn evaluate_assignable(&mut self, node: &PrivateNode) -> Result<GreenValue, String> {
         let literal_value = match &node {
+            // 変数は宣言された型を保持しているため、空の配列も型が定まる
+            PrivateNode::Variable { name, .. } => return self.variable_manager.get_value(name),
+            // 選ばれた方の値のみを評価する
+            PrivateNode::Conditional { condition, then_value, else_value } => {
+                if self.evaluate_condition(condition, ErrorCode::Runtime036)? {
+                    return self.evaluate_assignable(then_value)
+                } else {
+                    return self.evaluate_assignable(else_value)
+                }
+            },
             PrivateNode::Compare{ operator: _, left: _, right: _ } | PrivateNode::Arithmetic{ operator: _, left: _, right: _ }
-            | PrivateNode::Variable { name: _ } | PrivateNode::Logical{ operator: _, left: _, right: _ } => {
+            | PrivateNode::Logical{ operator: _, left: _, right: _ }
+            | PrivateNode::ArrayLiteral { elements: _ } | PrivateNode::Index { array: _, index: _ } => {
                 self.evaluate_expression(node)?
             },
-            PrivateNode::FunctionCall { name:_, arguments:_ , return_flg:_} => {
-                self.execute_function(node)?.ok_or("err")?.value
+            // 関数の戻り値とyieldされた値は型を保持しているため、空の配列も型が定まる
+            PrivateNode::FunctionCall { name, arguments:_ , return_flg:_, .. } => {
+                match self.execute_function(node)? {
+                    Some(value) => return Ok(value),
+                    None => return Err(ErrorMessage::global().get_error_message(
+                        ErrorContext::new(
+                            ErrorCode::Runtime034,
+                            None, None,
+                            vec![("function_name", name)],
+                        )
+                    )?),
+                }
             },
             PrivateNode::Literal{ value: _ } => self.evaluate_literal(node)?,
+            PrivateNode::CoroutineResume { task_name } => {
+                match self.resume_task(task_name)? {
+                    Some(value) => return Ok(value),
+                    None => return Err(ErrorMessage::global().get_error_message(
+                        ErrorContext::new(
+                            ErrorCode::Runtime029,
+                            None, None,
+                            vec![("task_name", task_name)],
+                        )
+                    )?),
+                }
+            },
             _ => return Err(ErrorMessage::global().get_error_message(
                 ErrorContext::new(
                     ErrorCode::Runtime005,
@@ -321,12 +1081,9 @@ impl Interpreter {
             )?),
         };
 
-        match literal_value {
-            LiteralValue::Bool(_) => Ok(GreenValue::new(Type::Bool, literal_value)),
-            LiteralValue::Float(_) => Ok(GreenValue::new(Type::Float, literal_value)),
-            LiteralValue::Int(_) => Ok(GreenValue::new(Type::Int, literal_value)),
-            LiteralValue::String(_) => Ok(GreenValue::new(Type::String, literal_value)),
-            LiteralValue::Null => Err("Null値には未対応".to_string()),
+        match literal_value.value_type() {
+            Some(value_type) => Ok(GreenValue::new(value_type, literal_value)),
+            None => Err("Null値には未対応".to_string()),
         }
     }
 
@@ -381,9 +1138,9 @@ impl Interpreter {
                                         ErrorCode::Runtime015,
                                         None, None,
                                         vec![
-                                            ("left", &left_value.to_string()),
+                                            ("left", &left_value.describe()),
                                             ("operator", &operator.to_string()),
-                                            ("right", &right_value.to_string()),
+                                            ("right", &right_value.describe()),
                                         ],
                                     )
                                 )?)
@@ -418,28 +1175,24 @@ impl Interpreter {
                         let result = self.compare_values(operator, left_value, right_value)?;
                         Ok(LiteralValue::Bool(result))
                     },
+                    // 文字列は辞書順で比較する
                     (LiteralValue::String(left_value), LiteralValue::String(right_value)) => {
-                        match operator {
-                            Comparison::Equal => Ok(LiteralValue::Bool(left_value == right_value)),
-                            Comparison::NotEqual => Ok(LiteralValue::Bool(left_value != right_value)),
-                            _ => Err(ErrorMessage::global().get_error_message(
-                                ErrorContext::new(
-                                    ErrorCode::Runtime006,
-                                    None, None,
-                                    vec![("operator", &operator.to_string())],
-                                )
-                            )?),
-                        }
+                        let result = self.compare_values(operator, left_value, right_value)?;
+                        Ok(LiteralValue::Bool(result))
                     },
                     (left_value, right_value) => {
+                        let error_code = match operator {
+                            Comparison::Equal | Comparison::NotEqual => ErrorCode::Runtime024,
+                            _ => ErrorCode::Runtime016,
+                        };
                         Err(ErrorMessage::global().get_error_message(
                             ErrorContext::new(
-                                ErrorCode::Runtime016,
+                                error_code,
                                 None, None,
                                 vec![
-                                    ("left", &left_value.to_string()),
+                                    ("left", &left_value.describe()),
                                     ("operator", &operator.to_string()),
-                                    ("right", &right_value.to_string()),
+                                    ("right", &right_value.describe()),
                                 ],
                             )
                         )?)
@@ -456,6 +1209,9 @@ impl Interpreter {
                     Some(right) => {
                         let left_literal = self.evaluate_expression(left)?;
                         let right_literal = self.evaluate_expression(right)?;
+                        if *operator == Arithmetic::Concat {
+                            return self.concat_values(&left_literal, &right_literal)
+                        }
                         match (&left_literal, &right_literal) {
                             (LiteralValue::Int(left_value), LiteralValue::Int(right_value)) => {
                                 match operator {
@@ -463,6 +1219,7 @@ impl Interpreter {
                                     Arithmetic::Minus => Ok(LiteralValue::Int(left_value - right_value)),
                                     Arithmetic::Multiply => Ok(LiteralValue::Int(left_value * right_value)),
                                     Arithmetic::Divide => Ok(LiteralValue::Int(left_value / right_value)),
+                                    Arithmetic::Concat => unreachable!(),
                                 }
                             },
                             (LiteralValue::Int(_), LiteralValue::Float(_)) |
@@ -483,6 +1240,7 @@ impl Interpreter {
                                     Arithmetic::Minus => Ok(LiteralValue::Float(left_value - right_value)),
                                     Arithmetic::Multiply => Ok(LiteralValue::Float(left_value * right_value)),
                                     Arithmetic::Divide => Ok(LiteralValue::Float(left_value / right_value)),
+                                    Arithmetic::Concat => unreachable!(),
                                 }
                             },
                             _ => Err(ErrorMessage::global().get_error_message(
@@ -490,9 +1248,9 @@ impl Interpreter {
                                     ErrorCode::Runtime015,
                                     None, None,
                                     vec![
-                                        ("left", &left_literal.to_string()),
+                                        ("left", &left_literal.describe()),
                                         ("operator", &operator.to_string()),
-                                        ("right", &right_literal.to_string()),
+                                        ("right", &right_literal.describe()),
                                     ],
                                 )
                             )?)
@@ -541,11 +1299,48 @@ impl Interpreter {
                     },
                 }
             },
-            PrivateNode::Variable { name } => {
+            PrivateNode::Variable { name, .. } => {
                 let variable = self.variable_manager.get_variable(name)?;
                 Ok(variable)
             },
             PrivateNode::Literal { value: _ } => self.evaluate_literal(node),
+            PrivateNode::Conditional { .. } | PrivateNode::FunctionCall { .. } => Ok(self.evaluate_assignable(node)?.value),
+            PrivateNode::ArrayLiteral { elements } => {
+                let values = self.evaluate_argument(elements)?;
+                Ok(LiteralValue::Array(values.into_iter().map(|x| x.value).collect()))
+            },
+            PrivateNode::Index { array, index } => {
+                let array = self.evaluate_expression(array)?;
+                let index = self.evaluate_expression(index)?;
+                match (array, index) {
+                    (LiteralValue::Array(elements), LiteralValue::Int(index)) => {
+                        match usize::try_from(index).ok().and_then(|i| elements.get(i)) {
+                            Some(element) => Ok(element.clone()),
+                            None => Err(ErrorMessage::global().get_error_message(
+                                ErrorContext::new(
+                                    ErrorCode::Runtime022,
+                                    None, None,
+                                    vec![
+                                        ("length", &elements.len().to_string()),
+                                        ("index", &index.to_string()),
+                                    ],
+                                )
+                            )?),
+                        }
+                    },
+                    (array, index) => Err(ErrorMessage::global().get_error_message(
+                        ErrorContext::new(
+                            ErrorCode::Runtime015,
+                            None, None,
+                            vec![
+                                ("left", &array.describe()),
+                                ("operator", "[]"),
+                                ("right", &index.describe()),
+                            ],
+                        )
+                    )?),
+                }
+            },
             _ => Err(ErrorMessage::global().get_error_message(
                 ErrorContext::new(
                     ErrorCode::Runtime003,
@@ -572,8 +1367,29 @@ impl Interpreter {
         }
     }
 
+    /// 文字列の連結（数値は文字列に変換する）
+    fn concat_values(&mut self, left: &LiteralValue, right: &LiteralValue) -> Result<LiteralValue, String> {
+        match (left, right) {
+            (LiteralValue::Int(_) | LiteralValue::Float(_) | LiteralValue::String(_),
+             LiteralValue::Int(_) | LiteralValue::Float(_) | LiteralValue::String(_)) => {
+                Ok(LiteralValue::String(left.to_string() + &right.to_string()))
+            },
+            _ => Err(ErrorMessage::global().get_error_message(
+                ErrorContext::new(
+                    ErrorCode::Runtime015,
+                    None, None,
+                    vec![
+                        ("left", &left.describe()),
+                        ("operator", &Arithmetic::Concat.to_string()),
+                        ("right", &right.describe()),
+                    ],
+                )
+            )?),
+        }
+    }
+
     /// 比較処理
-    fn compare_values(&mut self, operator: &Comparison, left: f64, right: f64) -> Result<bool, String> {
+    fn compare_values<T: PartialOrd>(&mut self, operator: &Comparison, left: T, right: T) -> Result<bool, String> {
         match operator {
             Comparison::Equal => Ok(left == right),
             Comparison::NotEqual => Ok(left != right),
@@ -594,8 +1410,17 @@ impl Interpreter {
 
 }
 
-pub fn execute(semantic: &Semantic) -> Result<(), String> {
-    let mut interpreter = Interpreter::new(semantic);
+pub fn execute(semantic: &Semantic, option: &ExecuteOption) -> Result<(), String> {
+    let mut interpreter = Interpreter::new(semantic, option);
+    interpreter.execute_program()?;
+    Ok(())
+}
+
+/// print関数などの出力先を指定して実行する
+/// 
+/// 出力を取り込む場合は`CaptureBuffer`を渡す
+pub fn execute_with_writer(semantic: &Semantic, option: &ExecuteOption, writer: Box<dyn Write>) -> Result<(), String> {
+    let mut interpreter = Interpreter::with_writer(semantic, option, writer);
     interpreter.execute_program()?;
     Ok(())
 }
\ No newline at end of file
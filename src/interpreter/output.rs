@@ -0,0 +1,141 @@
+use std::{cell::RefCell, io::Write, rc::Rc};
+use crate::error::{
+    error_code::ErrorCode, error_context::ErrorContext, error_message::ErrorMessage
+};
+
+/// print関数が出力する改行文字
+#[derive(Debug, Clone, Copy, Default, PartialEq, clap::ValueEnum)]
+pub enum LineEnding {
+    /// `\n`
+    #[default]
+    Lf,
+    /// `\r\n`
+    Crlf,
+}
+
+impl LineEnding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::Crlf => "\r\n",
+        }
+    }
+}
+
+/// print関数などの出力先
+/// 
+/// 出力したバイト数を数え、上限を超える出力はエラーにする
+pub struct Output {
+    /// 書き込み先（既定は標準出力）
+    writer: Box<dyn Write>,
+    /// 出力済みのバイト数
+    written_bytes: usize,
+    /// 出力できるバイト数の上限
+    max_bytes: Option<usize>,
+    /// 行末に出力する改行文字
+    line_ending: LineEnding,
+}
+
+impl Output {
+    pub fn new(max_bytes: Option<usize>, line_ending: LineEnding) -> Self {
+        Self::with_writer(max_bytes, line_ending, Box::new(std::io::stdout()))
+    }
+
+    /// 書き込み先を指定して作成する
+    pub fn with_writer(max_bytes: Option<usize>, line_ending: LineEnding, writer: Box<dyn Write>) -> Self {
+        Self {
+            writer,
+            written_bytes: 0,
+            max_bytes,
+            line_ending,
+        }
+    }
+
+    /// 改行を含まない出力を即座に表示する
+    pub fn flush(&mut self) -> Result<(), String> {
+        self.writer.flush().map_err(|e| e.to_string())
+    }
+
+    /// 文字列と改行文字の出力
+    pub fn write_line(&mut self, text: &str) -> Result<(), String> {
+        self.write(&format!("{}{}", text, self.line_ending.as_str()))
+    }
+
+    /// 文字列の出力
+    /// 
+    /// ## Argments
+    /// 
+    /// - `text` - 出力する文字列
+    /// 
+    /// ## Example
+    /// 
+    /// ```
+    /// use green::interpreter::output::{CaptureBuffer, LineEnding, Output};
+    /// 
+    /// let buffer = CaptureBuffer::default();
+    /// let mut output = Output::with_writer(None, LineEnding::Lf, Box::new(buffer.clone()));
+    /// output.write("result\n").unwrap();
+    /// assert_eq!(buffer.contents(), "result\n");
+    /// ```
+    pub fn write(&mut self, text: &str) -> Result<(), String> {
+        let written_bytes = self.written_bytes + text.len();
+        if let Some(max_bytes) = self.max_bytes {
+            if written_bytes > max_bytes {
+                return Err(ErrorMessage::global().get_error_message(
+                    ErrorContext::new(
+                        ErrorCode::Runtime023,
+                        None, None,
+                        vec![("max_bytes", &max_bytes.to_string())],
+                    )
+                )?)
+            }
+        }
+
+        if let Err(e) = self.writer.write_all(text.as_bytes()) {
+            return Err(e.to_string())
+        }
+        self.written_bytes = written_bytes;
+        Ok(())
+    }
+}
+
+/// 出力を取り込むバッファ
+/// 
+/// 複製したバッファは内容を共有するため、インタプリタに渡した後も出力を参照できる
+/// 
+/// ## Example
+/// 
+/// ```
+/// use green::{analyzer::semantic, interpreter::{execute::{execute_with_writer, ExecuteOption}, output::{CaptureBuffer, LineEnding}}, lexer::lexical_analyzer, parser::parser};
+/// 
+/// let (tokens, _) = lexical_analyzer::lex("function main() { println(1); }");
+/// let (ast, _) = parser::parse(tokens);
+/// let semantic = semantic::analyze(&ast);
+/// let option = ExecuteOption { debug: false, max_output_bytes: None, line_ending: LineEnding::Lf, max_call_depth: 1000, float_precision: None };
+/// 
+/// let buffer = CaptureBuffer::default();
+/// execute_with_writer(&semantic, &option, Box::new(buffer.clone())).unwrap();
+/// assert_eq!(buffer.contents(), "1\n");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CaptureBuffer {
+    bytes: Rc<RefCell<Vec<u8>>>,
+}
+
+impl CaptureBuffer {
+    /// 取り込んだ出力（UTF-8として不正なバイトは置き換える）
+    pub fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.bytes.borrow()).into_owned()
+    }
+}
+
+impl Write for CaptureBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.bytes.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
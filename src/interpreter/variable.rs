@@ -25,9 +25,14 @@ impl VariableManager {
     }
 
     pub fn get_variable(&mut self, name: &str) -> Result<LiteralValue, String> {
+        self.get_value(name).map(|variable| variable.value)
+    }
+
+    /// 変数の値を型と合わせて取得する
+    pub fn get_value(&mut self, name: &str) -> Result<GreenValue, String> {
         for scope in self.scopes.iter().rev() {
             if let Some(variable) = scope.get(name) {
-                return Ok(variable.value.clone());
+                return Ok(variable.clone());
             }
         }
         Err(ErrorMessage::global().get_error_message(
@@ -69,6 +74,21 @@ impl VariableManager {
         )?)
     }
 
+    /// 現在参照できる変数の一覧（名前順）
+    /// 
+    /// 内側のスコープの変数が、外側の同名の変数より優先される
+    pub fn variables(&self) -> Vec<(String, GreenValue)> {
+        let mut visible = HashMap::new();
+        for scope in &self.scopes {
+            for (name, value) in scope {
+                visible.insert(name.clone(), value.clone());
+            }
+        }
+        let mut variables: Vec<(String, GreenValue)> = visible.into_iter().collect();
+        variables.sort_by(|a, b| a.0.cmp(&b.0));
+        variables
+    }
+
     pub fn push_scope(&mut self) {
         self.scopes.push(HashMap::new());
     }
@@ -76,4 +96,39 @@ impl VariableManager {
     pub fn pop_scope(&mut self) {
         self.scopes.pop();
     }
+
+    /// 保存しておいた変数を持つスコープを追加する
+    pub fn push_scope_with(&mut self, variables: HashMap<String, GreenValue>) {
+        self.scopes.push(variables);
+    }
+
+    /// 最も内側のスコープを取り除き、その変数を返す
+    pub fn take_scope(&mut self) -> HashMap<String, GreenValue> {
+        self.scopes.pop().unwrap_or_default()
+    }
+
+    /// 現在のスコープの深さ
+    pub fn depth(&self) -> usize {
+        self.scopes.len()
+    }
+
+    /// 関数呼び出しの前後でスコープの深さが一致しているかの検証
+    /// 
+    /// スコープの積み残しや取りすぎを検出するため、デバッグビルドでのみ検証する
+    pub fn check_depth(&self, expected: usize, function_name: &str) -> Result<(), String> {
+        if !cfg!(debug_assertions) || self.depth() == expected {
+            return Ok(())
+        }
+        Err(ErrorMessage::global().get_error_message(
+            ErrorContext::new(
+                ErrorCode::Runtime025,
+                None, None,
+                vec![
+                    ("function", function_name),
+                    ("expected", &expected.to_string()),
+                    ("actual", &self.depth().to_string()),
+                ],
+            )
+        )?)
+    }
 }
\ No newline at end of file
@@ -7,6 +7,9 @@ use crate::{
     }, lexer::token::{Token, TokenKind}
 };
 
+/// 識別子、文字列リテラルの長さ（文字数）の上限の既定値
+pub const DEFAULT_MAX_TOKEN_LENGTH: usize = 65536;
+
 #[derive(Debug, Clone)]
 pub struct Lexer<'a> {
     chars: Peekable<Chars<'a>>,
@@ -14,10 +17,14 @@ pub struct Lexer<'a> {
     row: u32,
     col: u32,
     errors: Vec<ErrorContext>,
+    /// 識別子、文字列リテラルの長さ（文字数）の上限
+    max_length: usize,
+    /// コメントと空白もトークン列に含めるか
+    keep_trivia: bool,
 }
 
 impl<'a> Lexer<'a> {
-    fn new(text: &'a str) -> Self {
+    fn new(text: &'a str, max_length: usize) -> Self {
         let chars = text.chars().peekable();
         Self {
             chars,
@@ -25,22 +32,37 @@ impl<'a> Lexer<'a> {
             row: 1,
             col: 1,
             errors: Vec::new(),
+            max_length,
+            keep_trivia: false,
         }
     }
 
     fn tokenize(&mut self) -> (Vec<Token>, Vec<ErrorContext>) {
         while let Some(&char) = self.chars.peek() {
             match char {
-                ' ' | '\n' | '\r' | '\t' =>  self.next_char(),
+                ' ' | '\n' | '\r' | '\t' => self.lex_whitespace(),
                 '(' => {self.push_token(TokenKind::LParen); self.next_char();},
                 ')' => {self.push_token(TokenKind::RParen); self.next_char();},
                 '{' => {self.push_token(TokenKind::LBrace); self.next_char();},
                 '}' => {self.push_token(TokenKind::RBrace); self.next_char();},
+                '[' => {self.push_token(TokenKind::LBracket); self.next_char();},
+                ']' => {self.push_token(TokenKind::RBracket); self.next_char();},
                 ':' => {self.push_token(TokenKind::Colon); self.next_char();}
+                '?' => {self.push_token(TokenKind::Question); self.next_char();},
                 ';' => {self.push_token(TokenKind::Semicolon); self.next_char();},
                 ',' => {self.push_token(TokenKind::Comma); self.next_char();},
+                '.' if self.chars.clone().nth(1).is_some_and(|c| c.is_ascii_digit()) => {
+                    match self.lex_number() {
+                        Ok(_) => {},
+                        Err(e) => self.errors.push(e),
+                    }
+                },
+                '.' if self.chars.clone().take(3).eq("...".chars()) => {
+                    self.push_token(TokenKind::Ellipsis);
+                    for _ in 0..3 { self.next_char(); }
+                },
                 '.' => {self.push_token(TokenKind::Dot); self.next_char();}
-                '+' | '*' => {
+                '+' | '*' | '~' => {
                     match Arithmetic::from_str(&char.to_string()) {
                         Some(operator) => self.push_token(TokenKind::ArithmeticOperator(operator)),
                         _ => unreachable!(),
@@ -59,6 +81,12 @@ impl<'a> Lexer<'a> {
                         Err(e) => self.errors.push(e),
                     }
                 },
+                '\'' => {
+                    match self.lex_label() {
+                        Ok(_) => {},
+                        Err(e) => self.errors.push(e),
+                    }
+                },
                 '"' => {
                     match self.lex_string() {
                         Ok(_) => {},
@@ -83,13 +111,13 @@ impl<'a> Lexer<'a> {
                         Err(e) => self.errors.push(e),
                     }
                 },
-                _ if char.is_alphabetic() => {
+                _ if is_identifier_start(char) => {
                     match self.lex_identifier() {
                         Ok(_) => {},
                         Err(e) => self.errors.push(e),
                     }
                 },
-                _ if char.is_numeric() => {
+                _ if char.is_ascii_digit() => {
                     match self.lex_number() {
                         Ok(_) => {},
                         Err(e) => self.errors.push(e),
@@ -117,11 +145,57 @@ impl<'a> Lexer<'a> {
     /// lexer.lex_string()?;
     /// ```
     fn lex_string(&mut self) -> Result<(), ErrorContext> {
+        let (start_row, start_col) = (self.row, self.col);
         self.next_char();  // 最初の「"」をスキップ
         let mut string = String::new();
+        let mut length = 0;
+        // 最初に見つかったエスケープシーケンス、埋め込み式のエラー（文字列の末尾まで読んでから返す）
+        let mut content_error = None;
+        // 埋め込み式（`${expr}`）ごとの、直前の文字列と式のトークン
+        let mut interpolations = Vec::new();
         while let Some(&c) = self.chars.peek() {
             if c == '"' || c == '\n' || c == '\r' { break; }
-            string.push(c);
+            if c == '$' && self.chars.clone().nth(1) == Some('{') {
+                match self.lex_interpolation() {
+                    Ok(tokens) => interpolations.push((std::mem::take(&mut string), tokens)),
+                    Err(e) => { content_error.get_or_insert(e); },
+                }
+                continue;
+            }
+            if c == '\\' {
+                let escape_col = self.col;
+                self.next_char();  // `\`をスキップ
+                let escaped = match self.chars.peek() {
+                    Some('n') => '\n',
+                    Some('t') => '\t',
+                    Some('r') => '\r',
+                    Some('"') => '"',
+                    Some('\\') => '\\',
+                    Some('$') => '$',
+                    Some('\n') | Some('\r') | None => break,
+                    Some(&other) => {
+                        if content_error.is_none() {
+                            content_error = Some(ErrorContext::new(
+                                ErrorCode::Lex007,
+                                Some(self.row), Some(escape_col),
+                                vec![("escape", &format!("\\{}", other))],
+                            ));
+                        }
+                        other
+                    },
+                };
+                length += 1;
+                if length <= self.max_length {
+                    string.push(escaped);
+                }
+                self.next_char();
+                continue;
+            }
+            // 上限を超えた分は読み飛ばし、文字列を確保しない
+            length += 1;
+            if length <= self.max_length {
+                string.push(c);
+            }
             self.next_char();
         }
 
@@ -135,10 +209,90 @@ impl<'a> Lexer<'a> {
             );
         }
         self.next_char();   // 閉じる「"」をスキップ
-        self.push_token(TokenKind::StringLiteral(string));
+        if let Some(e) = content_error {
+            return Err(e)
+        }
+        if length > self.max_length {
+            return Err(self.too_long_error("文字列", length, start_row, start_col))
+        }
+        if interpolations.is_empty() {
+            self.push_token(TokenKind::StringLiteral(string));
+            return Ok(())
+        }
+
+        // `("a" ~ to_string(expr) ~ "b")`のトークン列に展開する
+        self.push_token_with_location(TokenKind::LParen, start_row, start_col);
+        for (text, tokens) in interpolations {
+            self.push_token_with_location(TokenKind::StringLiteral(text), start_row, start_col);
+            self.push_token_with_location(TokenKind::ArithmeticOperator(Arithmetic::Concat), start_row, start_col);
+            self.push_token_with_location(TokenKind::Identifier("to_string".to_string()), start_row, start_col);
+            self.push_token_with_location(TokenKind::LParen, start_row, start_col);
+            self.tokens.extend(tokens);
+            self.push_token_with_location(TokenKind::RParen, start_row, start_col);
+            self.push_token_with_location(TokenKind::ArithmeticOperator(Arithmetic::Concat), start_row, start_col);
+        }
+        self.push_token_with_location(TokenKind::StringLiteral(string), start_row, start_col);
+        self.push_token_with_location(TokenKind::RParen, start_row, start_col);
         Ok(())
     }
 
+    /// 文字列に埋め込まれた式（`${expr}`）の字句解析
+    /// 
+    /// 式のトークンを、元の文字列中の位置に合わせて返す
+    fn lex_interpolation(&mut self) -> Result<Vec<Token>, ErrorContext> {
+        let (start_row, start_col) = (self.row, self.col);
+        self.next_char();  // `$`をスキップ
+        self.next_char();  // `{`をスキップ
+        let expression_col = self.col;
+
+        let mut source = String::new();
+        let mut depth = 0;
+        loop {
+            match self.chars.peek() {
+                Some('}') if depth == 0 => break,
+                Some('"') | Some('\n') | Some('\r') | None => {
+                    return Err(ErrorContext::new(
+                        ErrorCode::Lex011,
+                        Some(start_row), Some(start_col),
+                        vec![],
+                    ))
+                },
+                Some(&c) => {
+                    match c {
+                        '{' => depth += 1,
+                        '}' => depth -= 1,
+                        _ => {},
+                    }
+                    source.push(c);
+                    self.next_char();
+                },
+            }
+        }
+        self.next_char();  // `}`をスキップ
+
+        if source.trim().is_empty() {
+            return Err(ErrorContext::new(
+                ErrorCode::Lex012,
+                Some(start_row), Some(start_col),
+                vec![],
+            ))
+        }
+
+        // 次の文字を先読みする字句解析があるため、末尾に空白を補う
+        let (mut tokens, errors) = lex_with_limit(&format!("{} ", source), self.max_length);
+        tokens.pop();  // EOFを取り除く
+        if let Some(mut error) = errors.into_iter().next() {
+            error.row = Some(start_row);
+            error.col = error.col.map(|col| expression_col + col - 1);
+            return Err(error)
+        }
+        for token in &mut tokens {
+            token.row = start_row;
+            token.col = expression_col + token.col - 1;
+        }
+        Ok(tokens)
+    }
+
     /// ハイフン記号の字句解析処理
     fn lex_hyphen(&mut self) -> Result<(), ErrorContext> {
         let start_row = self.row;
@@ -166,6 +320,23 @@ impl<'a> Lexer<'a> {
         Ok(())
     }
 
+    /// 空白の字句解析処理
+    /// 
+    /// コメントと空白を含める場合のみ、連続する空白を1つのトークンにする
+    fn lex_whitespace(&mut self) {
+        if !self.keep_trivia {
+            self.next_char();
+            return
+        }
+        let (start_row, start_col) = (self.row, self.col);
+        let mut whitespace = String::new();
+        while let Some(&c @ (' ' | '\n' | '\r' | '\t')) = self.chars.peek() {
+            whitespace.push(c);
+            self.next_char();
+        }
+        self.push_token_with_location(TokenKind::Whitespace(whitespace), start_row, start_col);
+    }
+
     /// スラッシュ記号の字句解析処理
     fn lex_slash(&mut self) -> Result<(), ErrorContext> {
         let start_row = self.row;
@@ -204,27 +375,48 @@ impl<'a> Lexer<'a> {
                         token_kind = TokenKind::DocComment(doc_comment);
                     },
                     _ => {
+                        // 改行は空白として扱う
+                        let mut comment = String::from("//");
                         while let Some(&c) = self.chars.peek() {
+                            if matches!(c, '\n' | '\r') { break; }
+                            comment.push(c);
                             self.next_char();
-                            if c == '\n' { break; }
                         }
-                        token_kind = TokenKind::Comment;
+                        token_kind = TokenKind::Comment(comment);
                     },
                 }
             },
             "/*" => {
                 self.next_char();
-                loop {
-                    let c = self.peek_char()?;
+                // ネストしたブロックコメントに対応するため、深さが0に戻るまで読み進める
+                let mut comment = String::from("/*");
+                let mut depth = 1;
+                while depth > 0 {
+                    let Some(c) = self.chars.peek().copied() else {
+                        // 閉じられていないコメントは開始位置を示す
+                        return Err(ErrorContext::new(
+                            ErrorCode::Lex004,
+                            Some(start_row), Some(start_col),
+                            vec![],
+                        ))
+                    };
                     self.next_char();
-                    if c == '*' {
-                        if self.peek_char()? == '/' {
+                    comment.push(c);
+                    match (c, self.chars.peek()) {
+                        ('*', Some('/')) => {
                             self.next_char();
-                            break;
-                        }
+                            comment.push('/');
+                            depth -= 1;
+                        },
+                        ('/', Some('*')) => {
+                            self.next_char();
+                            comment.push('*');
+                            depth += 1;
+                        },
+                        _ => {},
                     }
                 }
-                token_kind = TokenKind::Comment;
+                token_kind = TokenKind::Comment(comment);
             },
             _ => {
                 match Arithmetic::from_str("/") {
@@ -235,7 +427,8 @@ impl<'a> Lexer<'a> {
         } 
 
         match token_kind {
-            TokenKind::Comment => {},  // Commentはtokensに追加しない
+            TokenKind::Comment(_) if self.keep_trivia => {self.push_token_with_location(token_kind, start_row, start_col);},
+            TokenKind::Comment(_) => {},  // Commentはtokensに追加しない
             TokenKind::DocComment(_) => {self.push_token_with_location(token_kind, start_row, start_col);},  // 一時的にDocCommentも追加しない
             TokenKind::ArithmeticOperator(_) => {self.push_token_with_location(token_kind, start_row, start_col);},
             _ => { self.push_token(token_kind); },
@@ -281,13 +474,8 @@ impl<'a> Lexer<'a> {
                 }
                 self.next_char();
             },
-            _ => return Err(
-                ErrorContext::new(
-                    ErrorCode::Lex005,
-                    Some(self.row), Some(self.col),
-                    vec![("operator", &operator)],
-                )
-            ),
+            // 単独の`!`は`not`と同じ否定演算子
+            _ => self.push_token(TokenKind::LogicalOperator(Logical::Unary(UnaryLogical::Not))),
         }
         Ok(())
     }
@@ -327,17 +515,63 @@ impl<'a> Lexer<'a> {
         Ok(())
     }
 
+    /// ループのラベル（`'outer`）の字句解析処理
+    fn lex_label(&mut self) -> Result<(), ErrorContext> {
+        let start_col = self.col;
+        self.next_char();  // `'`をスキップ
+        let mut label = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if label.is_empty() && !is_identifier_start(c) { break; }
+            if !is_identifier_continue(c) { break; }
+            label.push(c);
+            self.next_char();
+        }
+        if label.is_empty() {
+            return Err(ErrorContext::new(
+                ErrorCode::Lex002,
+                Some(self.row), Some(start_col),
+                vec![("char", "'")],
+            ))
+        }
+        self.push_token_with_location(TokenKind::Label(label), self.row, start_col);
+        Ok(())
+    }
+
     /// 関数、変数、bool値などの字句解析処理
+    /// 
+    /// 使用できる文字は`is_identifier_start`と`is_identifier_continue`を参照
     fn lex_identifier(&mut self) -> Result<(), ErrorContext> {
         let start_col = self.col;
         let mut string = String::new();
+        let mut length = 0;
+        let mut invalid_char = None;
         loop {
             let c = self.peek_char()?;
-            
-            if !c.is_alphabetic() && !c.is_numeric() && c != '_' { break; }
-            string.push(c);
+
+            // 全角数字などASCII以外の数字は識別子に使えない
+            let is_invalid = c.is_numeric() && !c.is_ascii_digit();
+            if !is_invalid && !is_identifier_continue(c) { break; }
+            if is_invalid && invalid_char.is_none() {
+                invalid_char = Some((c, self.col));
+            }
+
+            // 上限を超えた分は読み飛ばし、文字列を確保しない
+            length += 1;
+            if length <= self.max_length {
+                string.push(c);
+            }
             self.next_char();
         }
+        if length > self.max_length {
+            return Err(self.too_long_error("識別子", length, self.row, start_col))
+        }
+        if let Some((c, col)) = invalid_char {
+            return Err(ErrorContext::new(
+                ErrorCode::Lex006,
+                Some(self.row), Some(col),
+                vec![("char", &c.to_string()), ("identifier", &string)],
+            ))
+        }
         match string.as_str() {
             "true" | "false" => {
                 match BoolKeyword::from_str(&string) {
@@ -357,7 +591,7 @@ impl<'a> Lexer<'a> {
                     None => unreachable!(),
                 }
             },
-            "let" | "function" | "coroutine" | "coro" => {
+            "let" | "const" | "function" | "coroutine" | "coro" | "import" => {
                 match DeclarationKeyword::from_str(&string) {
                     Some(keyword) => self.push_token_with_location(TokenKind::DeclarationKeyword(keyword), self.row, start_col),
                     None => unreachable!(),
@@ -393,24 +627,66 @@ impl<'a> Lexer<'a> {
         Ok(())
     }
 
-    /// 数値の字句解析
+    /// 数値の字句解析処理
+    /// 
+    /// 小数は整数部または小数部を省略できる（`.5`は`0.5`、`5.`は`5.0`）
+    /// `e`または`E`に続けて指数を書ける（`2.5e-4`）
+    /// 数字の間には区切り文字`_`を書ける（`1_000_000`）
     fn lex_number(&mut self) -> Result<(), ErrorContext> {
         let start_col = self.col;
-        let mut number_string = String::new();
+        let mut number_string = self.lex_digits()?;
 
-        while let Some(&c) = self.chars.peek() {
-            if c.is_numeric() {
-                number_string.push(c);
+        if self.chars.peek() == Some(&'.') {
+            self.next_char();
+            number_string.push('.');
+            number_string.push_str(&self.lex_digits()?);
+        }
+
+        if let Some(&e @ ('e' | 'E')) = self.chars.peek() {
+            let exponent_col = self.col;
+            self.next_char();
+            number_string.push(e);
+            if let Some(&sign @ ('+' | '-')) = self.chars.peek() {
                 self.next_char();
-            } else {
-                break;
+                number_string.push(sign);
             }
+            let exponent = self.lex_digits()?;
+            if exponent.is_empty() {
+                return Err(ErrorContext::new(
+                    ErrorCode::Lex008,
+                    Some(self.row), Some(exponent_col),
+                    vec![("number", &number_string)],
+                ))
+            }
+            number_string.push_str(&exponent);
         }
     
         self.push_token_with_location(TokenKind::NumberLiteral(number_string), self.row, start_col);
         Ok(())
     }
 
+    /// 連続する数字を読み進め、区切り文字`_`を取り除いて返す
+    /// 
+    /// `_`は数字の間にのみ書ける（先頭、末尾、連続はエラー）
+    fn lex_digits(&mut self) -> Result<String, ErrorContext> {
+        let (row, col) = (self.row, self.col);
+        let mut raw = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if !c.is_ascii_digit() && c != '_' { break; }
+            raw.push(c);
+            self.next_char();
+        }
+
+        if raw.starts_with('_') || raw.ends_with('_') || raw.contains("__") {
+            return Err(ErrorContext::new(
+                ErrorCode::Lex009,
+                Some(row), Some(col),
+                vec![("digits", &raw)],
+            ))
+        }
+        Ok(raw.replace('_', ""))
+    }
+
     /// tokenの追加
     /// 
     /// ## Argument
@@ -463,6 +739,34 @@ impl<'a> Lexer<'a> {
     }
 }
 
+/// 識別子の先頭に使える文字か
+/// 
+/// `_`とUnicodeの文字（`char::is_alphabetic`）を許可する
+fn is_identifier_start(c: char) -> bool {
+    c == '_' || c.is_alphabetic()
+}
+
+/// 識別子の2文字目以降に使える文字か
+/// 
+/// 先頭に使える文字に加えて、ASCIIの数字（`0`-`9`）を許可する
+fn is_identifier_continue(c: char) -> bool {
+    is_identifier_start(c) || c.is_ascii_digit()
+}
+
+impl Lexer<'_> {
+    fn too_long_error(&self, kind: &str, length: usize, row: u32, col: u32) -> ErrorContext {
+        ErrorContext::new(
+            ErrorCode::Lex010,
+            Some(row), Some(col),
+            vec![
+                ("kind", kind),
+                ("length", &length.to_string()),
+                ("max_length", &self.max_length.to_string()),
+            ],
+        )
+    }
+}
+
 /// トークナイズを行う
 /// 
 /// ## Argments
@@ -485,6 +789,22 @@ impl<'a> Lexer<'a> {
 /// };
 /// ```
 pub fn lex(text: &str) -> (Vec<Token>, Vec<ErrorContext>) {
-    let mut lexer = Lexer::new(text);
+    lex_with_limit(text, DEFAULT_MAX_TOKEN_LENGTH)
+}
+
+/// 識別子、文字列リテラルの長さの上限を指定してトークナイズを行う
+/// 
+/// 上限を超えた識別子、文字列リテラルは字句エラーになる
+pub fn lex_with_limit(text: &str, max_length: usize) -> (Vec<Token>, Vec<ErrorContext>) {
+    let mut lexer = Lexer::new(text, max_length);
+    lexer.tokenize()
+}
+
+/// コメントと空白（`TokenKind::Comment`、`TokenKind::Whitespace`）も含めてトークナイズを行う
+/// 
+/// フォーマッタなどでコメントと空白の位置を参照するためのもので、構文解析には使えない
+pub fn lex_with_trivia(text: &str, max_length: usize) -> (Vec<Token>, Vec<ErrorContext>) {
+    let mut lexer = Lexer::new(text, max_length);
+    lexer.keep_trivia = true;
     lexer.tokenize()
 }
@@ -19,11 +19,16 @@ pub enum TokenKind {
     RParen,
     LBrace,
     RBrace,
+    LBracket,
+    RBracket,
     Equal,
     Colon,
     Semicolon,
     Comma,
     Dot,
+    /// 可変長引数（`...`）
+    Ellipsis,
+    Question,
     RArrow,
 
     // キーワード
@@ -34,12 +39,18 @@ pub enum TokenKind {
     FunctionControl(FunctionControl),
     CoroutineControl(CoroutineControl),
 
+    /// ループのラベル（`'名前`）
+    Label(String),
+
     // 終端
     EOF,
 
     // その他
     DocComment(String),
-    Comment,
+    /// コメント（`//`、`/* */`を含む原文）
+    Comment(String),
+    /// 空白と改行の並び
+    Whitespace(String),
 }
 
 #[derive(Debug, Clone)]
@@ -66,9 +77,13 @@ impl TokenKind {
             Self::LParen => "(",
             Self::Equal => "=",
             Self::RBrace => "}",
+            Self::LBracket => "[",
+            Self::RBracket => "]",
             Self::RParen => ")",
             Self::Semicolon => ";",
             Self::Dot => ".",
+            Self::Ellipsis => "...",
+            Self::Question => "?",
             Self::RArrow => "->",
             
             Self::ControlKeyword(keyword) => &keyword.to_string(),
@@ -78,9 +93,12 @@ impl TokenKind {
             Self::FunctionControl(keyword) => &keyword.to_string(),
             Self::CoroutineControl(keyword) => &keyword.to_string(),
 
+            Self::Label(label) => &format!("'{}", label),
+
             Self::EOF => "EOF",
             
-            Self::Comment => "",
+            Self::Comment(_) => "",
+            Self::Whitespace(_) => "",
             Self::DocComment(_) => "",
             
         };
@@ -1,18 +1,113 @@
+use std::fs;
 use clap::Parser;
 
 use green::{
-    analyzer::semantic, cli, error::{
-        error_code::ErrorCode, error_context::ErrorContext, error_message::ErrorMessage
-    }, interpreter::execute, lexer::lexical_analyzer, parser::parser, utils::{ast_to_json::JsonData, misc}
+    analyzer::semantic, cli::{self, args::Phase}, error::{
+        color, error_code::ErrorCode, error_context::ErrorContext, error_message::ErrorMessage
+    }, interpreter::execute::{ExecuteOption, Interpreter}, lexer::{lexical_analyzer, token::TokenKind}, parser::{import, normalize, parser}, utils::{ast_to_json::JsonData, misc}
 };
 
+/// 実行終了時に表示する警告とエラーの件数
+#[derive(Default)]
+struct Summary {
+    warnings: usize,
+    errors: usize,
+    /// 実行時エラーが`assert`、`assert_eq`の失敗によるものか
+    assertion_failed: bool,
+}
+impl Summary {
+    /// 警告またはエラーがある場合のみ、標準エラー出力に件数を表示する
+    /// 
+    /// - `always` - 件数が0の場合も表示するか
+    fn print(&self, always: bool) {
+        if always || self.warnings > 0 || self.errors > 0 {
+            eprintln!("警告 {}件, エラー {}件", self.warnings, self.errors);
+        }
+    }
+}
+
+/// `assert`、`assert_eq`が失敗した場合の終了コード（その他のエラーは1）
+const ASSERTION_FAILURE_EXIT_CODE: i32 = 3;
+
+/// 関数呼び出し1回あたりに確保するスタックのサイズ
+const STACK_SIZE_PER_CALL: usize = 64 * 1024;
+/// 関数呼び出し以外の処理のために確保するスタックのサイズ
+const BASE_STACK_SIZE: usize = 8 * 1024 * 1024;
+
 fn main() -> Result<(), String> {
+    let cli = cli::args::Cli::parse();
+
+    // 関数呼び出しのネストが上限に達する前にスタックが溢れないよう、上限に応じたスタックを確保したスレッドで実行する
+    let stack_size = cli.max_call_depth.saturating_mul(STACK_SIZE_PER_CALL).saturating_add(BASE_STACK_SIZE);
+    let handle = std::thread::Builder::new()
+        .stack_size(stack_size)
+        .spawn(move || start(cli));
+    let handle = match handle {
+        Ok(handle) => handle,
+        Err(e) => {
+            eprintln!("{}", ErrorMessage::global().get_error_message(
+                ErrorContext::new(
+                    ErrorCode::Runtime043,
+                    None, None,
+                    vec![("stack_size", &stack_size.to_string()), ("message", &e.to_string())],
+                )
+            )?);
+            std::process::exit(1)
+        },
+    };
+    // 実行中のパニックは、メッセージを表示済みのためそのまま再開する
+    match handle.join() {
+        Ok(result) => result,
+        Err(payload) => std::panic::resume_unwind(payload),
+    }
+}
+
+fn start(cli: cli::args::Cli) -> Result<(), String> {
+    if cli.repl {
+        let option = ExecuteOption {
+            debug: cli.debug,
+            max_output_bytes: cli.max_output_bytes,
+            line_ending: cli.eol,
+            max_call_depth: cli.max_call_depth,
+            float_precision: cli.float_precision,
+        };
+        return cli::repl::run(&option)
+    }
+
+    let mut summary = Summary::default();
+    let result = run(&cli, &mut summary);
+    // 実行時エラーなど、件数を数えていないエラーは未表示のため、件数より先に表示して終了する
+    if let Err(e) = &result {
+        if summary.errors == 0 {
+            summary.errors = 1;
+            eprintln!("{}", e);
+            summary.print(cli.check);
+            let exit_code = if summary.assertion_failed { ASSERTION_FAILURE_EXIT_CODE } else { 1 };
+            std::process::exit(exit_code)
+        }
+    }
+    summary.print(cli.check);
+    result
+}
+
+fn run(cli: &cli::args::Cli, summary: &mut Summary) -> Result<(), String> {
     let mut error_flag = false;
+    let use_color = color::enabled(cli.color, cli.no_color);
 
-    let cli = cli::args::Cli::parse();
-    
     let content = match misc::load_file_content(&cli.file) {
         Ok(content) => content,
+        Err(misc::LoadError::InvalidUtf8 { offset }) => {
+            let error_msg = ErrorMessage::global().get_error_message(
+                ErrorContext::new(
+                    ErrorCode::Io004,
+                    None, None,
+                    vec![("file_name", &cli.file), ("offset", &offset.to_string())],
+                )
+            )?;
+            summary.errors += 1;
+            eprintln!("{}", error_msg);
+            return Err("error".to_string())
+        },
         Err(_) => {
             return Err(ErrorMessage::global().get_error_message(
                 ErrorContext::new(
@@ -25,18 +120,41 @@ fn main() -> Result<(), String> {
     };
 
 
-    let (tokens, errors) = lexical_analyzer::lex(&content);
+    let (tokens, errors) = if cli.dump_tokens_with_trivia {
+        lexical_analyzer::lex_with_trivia(&content, cli.max_identifier_length)
+    } else {
+        lexical_analyzer::lex_with_limit(&content, cli.max_identifier_length)
+    };
     if !errors.is_empty() {
         error_flag = true;
     }
     if error_flag {
+        summary.errors += errors.len();
         for error in errors {
-            let error_msg = ErrorMessage::global().get_error_message(error)?;
-            println!("{}", error_msg);
+            println!("{}", color::paint_error(error, use_color)?);
         }
         return Err("error".to_string())
     }
 
+    if cli.stop_after == Some(Phase::Lex) {
+        for token in &tokens {
+            println!("{}:{} {}", token.row, token.col, token.kind.to_string());
+        }
+        return Ok(())
+    }
+    if cli.dump_tokens_with_trivia {
+        // コメントと空白は改行などを含むため、エスケープして1行に出力する
+        for token in &tokens {
+            match &token.kind {
+                TokenKind::Whitespace(text) => println!("{}:{} Whitespace {:?}", token.row, token.col, text),
+                TokenKind::Comment(text) => println!("{}:{} Comment {:?}", token.row, token.col, text),
+                TokenKind::DocComment(text) => println!("{}:{} DocComment {:?}", token.row, token.col, text),
+                kind => println!("{}:{} {}", token.row, token.col, kind.to_string()),
+            }
+        }
+        return Ok(())
+    }
+
     let (ast, errors) = parser::parse(tokens);
 
     if !errors.is_empty() {
@@ -44,30 +162,110 @@ fn main() -> Result<(), String> {
     }
 
     if error_flag {
+        summary.errors += errors.len();
         for error in errors {
-            let error_msg = ErrorMessage::global().get_error_message(error)?;
-            println!("{}", error_msg);
+            println!("{}", color::paint_error(error, use_color)?);
         }
         return Err("error".to_string())
     }
 
-    let semantic = match semantic::semantic(&ast) {
-        Ok(semantic) => semantic,
+    if cli.ast || cli.stop_after == Some(Phase::Parse) {
+        print!("{}", ast);
+        return Ok(())
+    }
+
+    let ast = match import::resolve_imports(ast, &cli.file, cli.max_identifier_length) {
+        Ok(ast) => ast,
         Err(errors) => {
+            summary.errors += errors.len();
             for error in errors {
-                let error_msg = ErrorMessage::global().get_error_message(error)?;
-                println!("{}", error_msg);
+                println!("{}", color::paint_error(error, use_color)?);
             }
             return Err("error".to_string())
         }
     };
 
+    if cli.ast_hash {
+        println!("{:016x}", normalize::ast_hash(&ast));
+        return Ok(())
+    }
+
+    let mut semantic = semantic::analyze(&ast);
+    // 実行または実行前の検査の場合のみ、main関数を必須とする
+    if !cli.analyze && !cli.dump_scopes {
+        semantic.check_entry_point("main");
+    }
+
     // dbg!(&semantic);
 
-    if cli.analyze {
-        let _ = JsonData::new(semantic);
+    // 警告は標準エラー出力に出し、処理は続行する
+    for warning in &semantic.warnings {
+        let warning_msg = ErrorMessage::global().get_error_message(warning.clone())?;
+        eprintln!("{}", warning_msg);
+    }
+    if cli.deny_warnings {
+        summary.errors += semantic.warnings.len();
+    } else {
+        summary.warnings += semantic.warnings.len();
+    }
+
+    if !semantic.errors().is_empty() {
+        summary.errors += semantic.errors().len();
+        for error in semantic.errors() {
+            println!("{}", color::paint_error(error.clone(), use_color)?);
+        }
+        return Err("error".to_string())
+    }
+    if cli.deny_warnings && !semantic.warnings.is_empty() {
+        return Err("error".to_string())
+    }
+
+    if cli.fail_on_todo && !ast.todos.is_empty() {
+        summary.errors += ast.todos.len();
+        for todo in &ast.todos {
+            let error = ErrorContext::new(
+                ErrorCode::Parse009,
+                Some(todo.row), Some(todo.col),
+                vec![("comment", &todo.comment)],
+            );
+            println!("{}", color::paint_error(error, use_color)?);
+        }
+        return Err("error".to_string())
+    }
+
+    // 検査のみの場合は実行しない（件数は終了時に表示する）
+    if cli.check || cli.stop_after == Some(Phase::Analyze) {
+        return Ok(())
+    }
+
+    if cli.dump_scopes {
+        print!("{}", semantic.dump_scopes());
+    } else if cli.analyze {
+        let json_data = JsonData::new(semantic, cli.fold_constants);
+        let written = json_data.ast_to_json(!cli.json_compact)
+            .map_err(|e| e.to_string())
+            .and_then(|serialized| fs::write(&cli.output, serialized + "\n").map_err(|e| e.to_string()));
+        if let Err(e) = written {
+            return Err(ErrorMessage::global().get_error_message(
+                ErrorContext::new(
+                    ErrorCode::Io003,
+                    None, None,
+                    vec![("file_name", &cli.output), ("message", &e)],
+                )
+            )?)
+        }
+        println!("解析結果を出力: {}", cli.output);
     } else {
-        if let Err(e) = execute::execute(&semantic) {
+        let option = ExecuteOption {
+            debug: cli.debug,
+            max_output_bytes: cli.max_output_bytes,
+            line_ending: cli.eol,
+            max_call_depth: cli.max_call_depth,
+            float_precision: cli.float_precision,
+        };
+        let mut interpreter = Interpreter::new(&semantic, &option);
+        if let Err(e) = interpreter.execute_program() {
+            summary.assertion_failed = interpreter.assertion_failed();
             return Err(ErrorMessage::global().get_error_message(
                 ErrorContext::new(
                     ErrorCode::Runtime001,
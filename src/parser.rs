@@ -1,2 +1,5 @@
 pub mod parser;
-pub mod node;
\ No newline at end of file
+pub mod node;
+pub mod import;
+pub mod normalize;
+pub mod tree;
\ No newline at end of file
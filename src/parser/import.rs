@@ -0,0 +1,252 @@
+use std::{collections::{HashMap, HashSet}, path::{Path, PathBuf}};
+use super::{node::*, parser};
+use crate::{
+    error::{error_code::ErrorCode, error_context::ErrorContext},
+    lexer::lexical_analyzer,
+    utils::misc,
+};
+
+/// ファイルから参照できる定義
+#[derive(Debug, Clone, Default)]
+struct Definitions {
+    functions: Vec<FunctionDefinitionNode>,
+    coroutines: Vec<CoroutineDefinitionNode>,
+}
+
+impl Definitions {
+    /// 定義の追加
+    ///
+    /// 複数のファイルから同じファイルをインポートした場合に備え、同一の定義は1度だけ追加する
+    fn extend(&mut self, other: Definitions) {
+        for function in other.functions {
+            if !self.functions.contains(&function) {
+                self.functions.push(function);
+            }
+        }
+        for coroutine in other.coroutines {
+            if !self.coroutines.contains(&coroutine) {
+                self.coroutines.push(coroutine);
+            }
+        }
+    }
+
+    /// 全ての定義名と、定義内からの呼び出しに名前空間を付与する
+    fn namespace(mut self, alias: &str) -> Self {
+        let names = self.functions.iter().map(|function| function.name.clone())
+            .chain(self.coroutines.iter().map(|coroutine| coroutine.name.clone()))
+            .collect::<HashSet<_>>();
+
+        for function in &mut self.functions {
+            function.name = format!("{}.{}", alias, function.name);
+            rename_block(&mut function.block, alias, &names);
+        }
+        for coroutine in &mut self.coroutines {
+            coroutine.name = format!("{}.{}", alias, coroutine.name);
+            rename_block(&mut coroutine.block, alias, &names);
+        }
+        self
+    }
+}
+
+fn rename_block(block: &mut BlockNode, alias: &str, names: &HashSet<String>) {
+    for statement in &mut block.statements {
+        rename_node(statement, alias, names);
+    }
+}
+
+/// `names`に含まれる関数、コルーチンの呼び出しに名前空間を付与する
+fn rename_node(node: &mut PrivateNode, alias: &str, names: &HashSet<String>) {
+    match node {
+        PrivateNode::FunctionCall { name, arguments, .. } => {
+            if names.contains(name) {
+                *name = format!("{}.{}", alias, name);
+            }
+            for argument in arguments {
+                rename_node(argument, alias, names);
+            }
+        },
+        PrivateNode::CoroutineInstantiation { coroutine_name, .. } if names.contains(coroutine_name) => {
+            *coroutine_name = format!("{}.{}", alias, coroutine_name);
+        },
+        PrivateNode::VariableDeclaration { initializer: Some(initializer), .. } => {
+            rename_node(initializer, alias, names);
+        },
+        PrivateNode::VariableAssignment { expression, .. }
+        | PrivateNode::ArrayDestructuring { initializer: expression, .. }
+        | PrivateNode::ExpressionStatement { expression } => rename_node(expression, alias, names),
+        PrivateNode::ReturnStatement { assignalbe } => rename_node(assignalbe, alias, names),
+        PrivateNode::Yield { value: Some(value) } => rename_node(value, alias, names),
+        PrivateNode::IfStatement { condition_node, then_block, else_block } => {
+            rename_node(condition_node, alias, names);
+            rename_block(then_block, alias, names);
+            if let Some(else_block) = else_block {
+                rename_block(else_block, alias, names);
+            }
+        },
+        PrivateNode::LoopStatement { label:_, condition_node, block, else_block } => {
+            rename_node(condition_node, alias, names);
+            rename_block(block, alias, names);
+            if let Some(else_block) = else_block {
+                rename_block(else_block, alias, names);
+            }
+        },
+        PrivateNode::Logical { left, right, .. }
+        | PrivateNode::Arithmetic { left, right, .. } => {
+            rename_node(left, alias, names);
+            if let Some(right) = right {
+                rename_node(right, alias, names);
+            }
+        },
+        PrivateNode::Compare { left, right, .. } => {
+            rename_node(left, alias, names);
+            rename_node(right, alias, names);
+        },
+        PrivateNode::ArrayLiteral { elements } => {
+            for element in elements {
+                rename_node(element, alias, names);
+            }
+        },
+        PrivateNode::Index { array, index } => {
+            rename_node(array, alias, names);
+            rename_node(index, alias, names);
+        },
+        PrivateNode::Conditional { condition, then_value, else_value } => {
+            rename_node(condition, alias, names);
+            rename_node(then_value, alias, names);
+            rename_node(else_value, alias, names);
+        },
+        _ => {},
+    }
+}
+
+struct ImportResolver {
+    /// 解決中のファイル（循環インポートの検出用）
+    stack: Vec<PathBuf>,
+    /// 解決済みのファイルの定義
+    resolved: HashMap<PathBuf, Definitions>,
+    errors: Vec<ErrorContext>,
+    /// 識別子、文字列リテラルの長さの上限
+    max_token_length: usize,
+}
+
+impl ImportResolver {
+    fn new(max_token_length: usize) -> Self {
+        Self {
+            stack: Vec::new(),
+            resolved: HashMap::new(),
+            errors: Vec::new(),
+            max_token_length,
+        }
+    }
+
+    /// ファイルのインポート文を再帰的に解決し、ファイルから参照できる定義を返す
+    fn resolve(&mut self, root: RootNode, path: PathBuf) -> Definitions {
+        let base_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        self.stack.push(path);
+
+        let mut definitions = Definitions::default();
+        for import in &root.imports {
+            let import_path = match base_dir.join(&import.path).canonicalize() {
+                Ok(import_path) => import_path,
+                Err(_) => {
+                    self.errors.push(ErrorContext::new(
+                        ErrorCode::Io002,
+                        Some(import.row), Some(import.col),
+                        vec![("file_name", &import.path)],
+                    ));
+                    continue;
+                },
+            };
+
+            if self.stack.contains(&import_path) {
+                self.errors.push(ErrorContext::new(
+                    ErrorCode::Parse007,
+                    Some(import.row), Some(import.col),
+                    vec![("file_name", &import.path)],
+                ));
+                continue;
+            }
+
+            let imported = match self.resolved.get(&import_path) {
+                Some(imported) => imported.clone(),
+                None => {
+                    let Some(ast) = self.load(import, &import_path) else { continue };
+                    let imported = self.resolve(ast, import_path.clone());
+                    self.resolved.insert(import_path, imported.clone());
+                    imported
+                },
+            };
+            match &import.alias {
+                Some(alias) => definitions.extend(imported.namespace(alias)),
+                None => definitions.extend(imported),
+            }
+        }
+
+        definitions.extend(Definitions { functions: root.functions, coroutines: root.coroutines });
+        self.stack.pop();
+        definitions
+    }
+
+    /// インポートするファイルの字句解析と構文解析
+    fn load(&mut self, import: &ImportNode, import_path: &Path) -> Option<RootNode> {
+        let content = match misc::load_file_content(&import_path.to_string_lossy()) {
+            Ok(content) => content,
+            Err(misc::LoadError::InvalidUtf8 { offset }) => {
+                self.errors.push(ErrorContext::new(
+                    ErrorCode::Io004,
+                    Some(import.row), Some(import.col),
+                    vec![("file_name", &import.path), ("offset", &offset.to_string())],
+                ));
+                return None
+            },
+            Err(_) => {
+                self.errors.push(ErrorContext::new(
+                    ErrorCode::Io002,
+                    Some(import.row), Some(import.col),
+                    vec![("file_name", &import.path)],
+                ));
+                return None
+            },
+        };
+        let (tokens, errors) = lexical_analyzer::lex_with_limit(&content, self.max_token_length);
+        if !errors.is_empty() {
+            self.errors.extend(errors);
+            return None
+        }
+        let (ast, errors) = parser::parse(tokens);
+        if !errors.is_empty() {
+            self.errors.extend(errors);
+            return None
+        }
+        Some(ast)
+    }
+}
+
+/// インポート文を解決し、インポートしたファイルの定義をルートノードへ統合する
+///
+/// `as`で名前空間を指定した場合、定義名は`名前空間.名前`になる
+///
+/// ## Argments
+///
+/// - `root` - `file_path`の構文解析の結果
+/// - `file_path` - インポート元のファイルのpath（相対パスの基準）
+/// - `max_token_length` - インポートするファイルの識別子、文字列リテラルの長さの上限
+pub fn resolve_imports(root: RootNode, file_path: &str, max_token_length: usize) -> Result<RootNode, Vec<ErrorContext>> {
+    let path = Path::new(file_path);
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+
+    let mut resolver = ImportResolver::new(max_token_length);
+    let todos = root.todos.clone();
+    let definitions = resolver.resolve(root, path);
+
+    if resolver.errors.is_empty() {
+        Ok(RootNode {
+            functions: definitions.functions,
+            coroutines: definitions.coroutines,
+            imports: Vec::new(),
+            todos,
+        })
+    } else {
+        Err(resolver.errors)
+    }
+}
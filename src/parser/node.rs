@@ -9,6 +9,9 @@ use crate::common::{
 pub struct RootNode {
     pub functions: Vec<FunctionDefinitionNode>,
     pub coroutines: Vec<CoroutineDefinitionNode>,
+    pub imports: Vec<ImportNode>,
+    /// Docコメント、プロセスコメント内の`@todo`
+    pub todos: Vec<TodoNode>,
 }
 
 
@@ -23,16 +26,44 @@ pub struct FunctionDefinitionNode {
     pub return_type: Option<Type>,
     pub block: BlockNode,
     pub doc: Option<String>,
+    /// Docコメントに`@memoize`が指定されている
+    pub memoize: bool,
+    /// 最後のパラメータが可変長引数（`...`）
+    /// 
+    /// 可変長引数のパラメータの型は、要素型の配列になる
+    pub is_variadic: bool,
 }
 
 /// コルーチン定義ノード（ルートノード直下）
 #[derive(Debug, PartialEq, Clone)]
 pub struct CoroutineDefinitionNode {
     pub name: String,
+    /// yieldする値の型
+    pub yield_type: Option<Type>,
     pub block: BlockNode,
     pub doc: Option<String>,
 }
 
+/// インポート文ノード（ルートノード直下）
+#[derive(Debug, PartialEq, Clone)]
+pub struct ImportNode {
+    /// インポート元のファイルからの相対パス
+    pub path: String,
+    /// 名前空間（`as`で指定）
+    pub alias: Option<String>,
+    pub row: u32,
+    pub col: u32,
+}
+
+/// `@todo`を含むコメントの行
+#[derive(Debug, PartialEq, Clone)]
+pub struct TodoNode {
+    /// `@todo`以降の文字列
+    pub comment: String,
+    pub row: u32,
+    pub col: u32,
+}
+
 /// 関数のパラメータ定義ノード
 #[derive(Debug, PartialEq, Clone)]
 pub struct ParameterNode {
@@ -58,35 +89,64 @@ pub enum PrivateNode {
         name: String,
         arguments: Vec<Self>,
         return_flg: bool,
+        row: u32,
+        col: u32,
     },
 
     /// コルーチンのインスタンス化
     CoroutineInstantiation {
         task_name: String,
         coroutine_name: String,
+        row: u32,
+        col: u32,
     },
 
-    /// コルーチンの再開
+    /// コルーチンの再開（式の場合はyieldされた値に評価される）
     CoroutineResume {
         task_name: String,
     },
-    Yield,
+    /// コルーチンの一時停止
+    Yield {
+        value: Option<Box<Self>>,
+    },
 
     /// 変数宣言
     VariableDeclaration {
         name: String,
-        variable_type: Type,
+        /// 型（省略した場合は`None`で、意味解析で初期値から推論する）
+        variable_type: Option<Type>,
         initializer: Option<Box<Self>>,
+        /// `const`で宣言された（再代入できない）
+        is_constant: bool,
         doc: Option<String>,
+        row: u32,
+        col: u32,
+    },
+    /// 配列の分割代入による変数宣言
+    ArrayDestructuring {
+        names: Vec<String>,
+        /// 代入する配列の型
+        variable_type: Type,
+        initializer: Box<Self>,
+        row: u32,
+        col: u32,
     },
     /// 変数代入
     VariableAssignment {
         name: String,
         expression: Box<Self>,
+        row: u32,
+        col: u32,
+    },
+    /// 式文（評価結果は破棄される）
+    ExpressionStatement {
+        expression: Box<Self>,
     },
     /// 変数呼び出し
     Variable {
         name: String,
+        row: u32,
+        col: u32,
     },
 
     /// If文
@@ -97,8 +157,11 @@ pub enum PrivateNode {
     },
     /// ループ文
     LoopStatement {
+        label: Option<String>,
         condition_node: Box<Self>,
         block: BlockNode,
+        /// breakせずに条件が偽になって終了した場合に実行するブロック
+        else_block: Option<BlockNode>,
     },
 
     /// return文
@@ -110,8 +173,14 @@ pub enum PrivateNode {
         comment: String,
     },
 
-    Break,
-    Continue,
+    /// break文（ラベルを省略した場合は最も内側のループ）
+    Break {
+        label: Option<String>,
+    },
+    /// continue文（ラベルを省略した場合は最も内側のループ）
+    Continue {
+        label: Option<String>,
+    },
     Error,
     
     // --------------------
@@ -138,4 +207,19 @@ pub enum PrivateNode {
     Literal {
         value: LiteralValue,
     },
+    /// 配列リテラル
+    ArrayLiteral {
+        elements: Vec<Self>,
+    },
+    /// 配列の要素の呼び出し
+    Index {
+        array: Box<Self>,
+        index: Box<Self>,
+    },
+    /// 条件式（`condition ? then_value : else_value`）
+    Conditional {
+        condition: Box<Self>,
+        then_value: Box<Self>,
+        else_value: Box<Self>,
+    },
 }
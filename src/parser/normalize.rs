@@ -0,0 +1,123 @@
+use super::node::*;
+
+/// 位置情報とドキュメントコメント（`@todo`を含む）を取り除いたASTを返す
+///
+/// 書式だけが異なるプログラムの構文解析結果を、`PartialEq`で比較できるようにする
+pub fn normalize(root: &RootNode) -> RootNode {
+    let mut root = root.clone();
+    for function in &mut root.functions {
+        function.doc = None;
+        normalize_block(&mut function.block);
+    }
+    for coroutine in &mut root.coroutines {
+        coroutine.doc = None;
+        normalize_block(&mut coroutine.block);
+    }
+    for import in &mut root.imports {
+        import.row = 0;
+        import.col = 0;
+    }
+    root.todos.clear();
+    root
+}
+
+/// 2つのASTが位置情報とドキュメントコメントを除いて等しいか
+pub fn structurally_equal(left: &RootNode, right: &RootNode) -> bool {
+    normalize(left) == normalize(right)
+}
+
+/// 2つのブロックが位置情報とコメントを除いて等しいか
+pub fn blocks_structurally_equal(left: &BlockNode, right: &BlockNode) -> bool {
+    let (mut left, mut right) = (left.clone(), right.clone());
+    normalize_block(&mut left);
+    normalize_block(&mut right);
+    left == right
+}
+
+/// 正規化したASTのハッシュ値
+///
+/// 空白やコメントだけの変更では変わらない。実行環境やRustのバージョンに依存しないよう、
+/// `Debug`による文字列表現をFNV-1a（64bit）でハッシュ化する
+pub fn ast_hash(root: &RootNode) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    format!("{:?}", normalize(root)).bytes().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(PRIME)
+    })
+}
+
+fn normalize_block(block: &mut BlockNode) {
+    block.statements.retain(|statement| !matches!(statement, PrivateNode::ProcessComment { .. }));
+    for statement in &mut block.statements {
+        normalize_node(statement);
+    }
+}
+
+fn normalize_node(node: &mut PrivateNode) {
+    match node {
+        PrivateNode::FunctionCall { arguments, row, col, .. } => {
+            (*row, *col) = (0, 0);
+            for argument in arguments {
+                normalize_node(argument);
+            }
+        },
+        PrivateNode::CoroutineInstantiation { row, col, .. }
+        | PrivateNode::Variable { row, col, .. } => (*row, *col) = (0, 0),
+        PrivateNode::VariableDeclaration { initializer, doc, row, col, .. } => {
+            (*row, *col) = (0, 0);
+            *doc = None;
+            if let Some(initializer) = initializer {
+                normalize_node(initializer);
+            }
+        },
+        PrivateNode::VariableAssignment { expression, row, col, .. }
+        | PrivateNode::ArrayDestructuring { initializer: expression, row, col, .. } => {
+            (*row, *col) = (0, 0);
+            normalize_node(expression);
+        },
+        PrivateNode::ExpressionStatement { expression } => normalize_node(expression),
+        PrivateNode::ReturnStatement { assignalbe } => normalize_node(assignalbe),
+        PrivateNode::Yield { value: Some(value) } => normalize_node(value),
+        PrivateNode::IfStatement { condition_node, then_block, else_block } => {
+            normalize_node(condition_node);
+            normalize_block(then_block);
+            if let Some(else_block) = else_block {
+                normalize_block(else_block);
+            }
+        },
+        PrivateNode::LoopStatement { label:_, condition_node, block, else_block } => {
+            normalize_node(condition_node);
+            normalize_block(block);
+            if let Some(else_block) = else_block {
+                normalize_block(else_block);
+            }
+        },
+        PrivateNode::Logical { left, right, .. }
+        | PrivateNode::Arithmetic { left, right, .. } => {
+            normalize_node(left);
+            if let Some(right) = right {
+                normalize_node(right);
+            }
+        },
+        PrivateNode::Compare { left, right, .. } => {
+            normalize_node(left);
+            normalize_node(right);
+        },
+        PrivateNode::ArrayLiteral { elements } => {
+            for element in elements {
+                normalize_node(element);
+            }
+        },
+        PrivateNode::Index { array, index } => {
+            normalize_node(array);
+            normalize_node(index);
+        },
+        PrivateNode::Conditional { condition, then_value, else_value } => {
+            normalize_node(condition);
+            normalize_node(then_value);
+            normalize_node(else_value);
+        },
+        _ => {},
+    }
+}
@@ -15,17 +15,22 @@ use crate::{
 struct Parser {
     tokens: Peekable<IntoIter<Token>>,
     block_stack: Vec<BlockType>,
+    /// 解析中のループのラベル
+    label_stack: Vec<String>,
     errors: Vec<ErrorContext>,
     doc_comment: String,
+    todos: Vec<TodoNode>,
 }
 
 impl Parser {
     fn new(tokens: Vec<Token>) -> Self {
         Self{
-            tokens: tokens.into_iter().peekable(),
+            tokens: merge_qualified_names(tokens).into_iter().peekable(),
             block_stack: Vec::new(),
+            label_stack: Vec::new(),
             errors: Vec::new(),
             doc_comment: String::new(),
+            todos: Vec::new(),
         }
     }
 
@@ -40,6 +45,7 @@ impl Parser {
     fn parse_global(&mut self) -> RootNode {
         let mut functions = Vec::new();
         let mut coroutines = Vec::new();
+        let mut imports = Vec::new();
 
         loop {
             let token = match self.peek_token(){
@@ -81,24 +87,31 @@ impl Parser {
                 TokenKind::DeclarationKeyword(keyword) => {
                     match keyword {
                         DeclarationKeyword::Function => {
-                            let function = match self.parse_function_definition() {
-                                Ok(node) => node,
+                            match self.parse_function_definition() {
+                                Ok(node) => functions.push(node),
                                 Err(e) => {
                                     self.errors.push(e);
-                                    break;
+                                    self.synchronize_global();
                                 },
-                            };
-                            functions.push(function);
+                            }
                         },
                         DeclarationKeyword::Coroutine => {
-                            let coroutine = match self.parse_coroutine_definition() {
-                                Ok(node) => node,
+                            match self.parse_coroutine_definition() {
+                                Ok(node) => coroutines.push(node),
                                 Err(e) => {
                                     self.errors.push(e);
-                                    break;
-                                }
-                            };
-                            coroutines.push(coroutine);
+                                    self.synchronize_global();
+                                },
+                            }
+                        },
+                        DeclarationKeyword::Import => {
+                            match self.parse_import() {
+                                Ok(node) => imports.push(node),
+                                Err(e) => {
+                                    self.errors.push(e);
+                                    self.synchronize_global();
+                                },
+                            }
                         },
                         _ => {
                             self.errors.push(
@@ -108,10 +121,14 @@ impl Parser {
                                     vec![("statement", &keyword.to_string()), ("block", "関数")],
                                 )
                             );
-                            break;
+                            self.synchronize_global();
                         },
                     }
                 },
+                TokenKind::FunctionControl(keyword) => {
+                    self.errors.push(Self::outside_function_error(&keyword.to_string(), "グローバルスコープ", token.row, token.col));
+                    self.synchronize_global();
+                },
                 _ => {
                     self.errors.push(
                         ErrorContext::new(
@@ -120,14 +137,48 @@ impl Parser {
                             vec![("token", &token.kind.to_string())],
                         )
                     );
-                    break;
+                    self.synchronize_global();
                 },
             }
         
             self.doc_comment = String::new();
         }
 
-        RootNode { functions, coroutines }
+        RootNode { functions, coroutines, imports, todos: std::mem::take(&mut self.todos) }
+    }
+
+    /// インポート文の構文解析
+    fn parse_import(&mut self) -> Result<ImportNode, ErrorContext> {
+        let import_token = self.next_token()?;
+        let token = self.next_token()?;
+        let path = match token.kind {
+            TokenKind::StringLiteral(path) => path,
+            _ => return Err(ErrorContext::new(
+                ErrorCode::Parse005,
+                Some(token.row), Some(token.col),
+                vec![("token", "ファイルパス")],
+            )),
+        };
+
+        // `as 名前空間`
+        let alias = match self.peek_token()?.kind {
+            TokenKind::Identifier(keyword) if keyword == "as" => {
+                self.next_token()?;
+                let token = self.next_token()?;
+                match token.kind {
+                    TokenKind::Identifier(alias) => Some(alias),
+                    _ => return Err(ErrorContext::new(
+                        ErrorCode::Parse005,
+                        Some(token.row), Some(token.col),
+                        vec![("token", "名前空間")],
+                    )),
+                }
+            },
+            _ => None,
+        };
+        self.check_next_token(TokenKind::Semicolon);
+
+        Ok(ImportNode { path, alias, row: import_token.row, col: import_token.col })
     }
 
     fn parse_function_definition(&mut self) -> Result<FunctionDefinitionNode, ErrorContext> {
@@ -143,7 +194,7 @@ impl Parser {
             ))
         }
 
-        let doc = self.get_doc_comment();
+        let (doc, memoize) = Self::split_memoize(self.get_doc_comment());
         
         let token = self.next_token()?;
         let function_name = match token.kind {
@@ -161,6 +212,7 @@ impl Parser {
 
         // 引数処理
         let mut parameters = Vec::new();
+        let mut is_variadic = false;
         loop {
             let token = self.peek_token()?;
             if token.kind == TokenKind::RParen { break; }
@@ -182,7 +234,7 @@ impl Parser {
             
             let type_token = self.next_token()?;
             let variable_type = match type_token.kind {
-                TokenKind::TypeName(type_name) => Type::from_keyword(&type_name),
+                TokenKind::TypeName(type_name) => self.parse_array_type(Type::from_keyword(&type_name)),
                 _ => {
                     self.errors.push(ErrorContext::new(
                         ErrorCode::Parse002,
@@ -192,6 +244,22 @@ impl Parser {
                     Type::Bool
                 },
             };
+
+            // 可変長引数は要素型の配列として受け取る
+            let variable_type = if self.peek_token()?.kind == TokenKind::Ellipsis {
+                let ellipsis = self.next_token()?;
+                if self.peek_token()?.kind != TokenKind::RParen {
+                    self.errors.push(ErrorContext::new(
+                        ErrorCode::Parse010,
+                        Some(ellipsis.row), Some(ellipsis.col),
+                        vec![("name", &name)],
+                    ));
+                }
+                is_variadic = true;
+                Type::Array(Box::new(variable_type))
+            } else {
+                variable_type
+            };
             
             let token = self.peek_token()?;
             match token.kind {
@@ -232,8 +300,9 @@ impl Parser {
                 let type_token = self.next_token()?;
                 match type_token.kind {
                     TokenKind::TypeName(type_name) => {
+                        let return_type = self.parse_array_type(Type::from_keyword(&type_name));
                         self.check_next_token(TokenKind::LBrace);
-                        Some(Type::from_keyword(&type_name))
+                        Some(return_type)
                     },
                     _ => {
                         return Err(ErrorContext::new(
@@ -265,10 +334,24 @@ impl Parser {
             return_type,
             block,
             doc,
+            memoize,
+            is_variadic,
         })
 
     }
 
+    /// 関数のDocコメントから`@memoize`の行を取り除く
+    /// 
+    /// ## Return
+    /// 
+    /// - (`@memoize`以外のDocコメント, `@memoize`が指定されているか)
+    fn split_memoize(doc: Option<String>) -> (Option<String>, bool) {
+        let Some(doc) = doc else { return (None, false) };
+        let (annotations, lines): (Vec<&str>, Vec<&str>) = doc.lines().partition(|line| line.trim() == "@memoize");
+        let doc = if lines.is_empty() { None } else { Some(lines.join("\n")) };
+        (doc, !annotations.is_empty())
+    }
+
     fn parse_coroutine_definition(&mut self) -> Result<CoroutineDefinitionNode, ErrorContext> {
         let token = self.next_token()?;
         if self.block_stack.last() != Some(&BlockType::Global) {
@@ -299,6 +382,24 @@ impl Parser {
         self.check_next_token(TokenKind::LParen);
 
         self.check_next_token(TokenKind::RParen);
+
+        let yield_type = match self.peek_token()?.kind {
+            TokenKind::RArrow => {
+                self.next_token()?;
+                let type_token = self.next_token()?;
+                match type_token.kind {
+                    TokenKind::TypeName(type_name) => Some(self.parse_array_type(Type::from_keyword(&type_name))),
+                    _ => {
+                        return Err(ErrorContext::new(
+                            ErrorCode::Parse002,
+                            Some(type_token.row), Some(type_token.col),
+                            vec![("token", &type_token.kind.to_string())],
+                        ))
+                    },
+                }
+            },
+            _ => None,
+        };
         self.check_next_token(TokenKind::LBrace);
         
         self.push_block(BlockType::Coroutine);
@@ -306,7 +407,7 @@ impl Parser {
         self.pop_block();
 
         self.check_next_token(TokenKind::RBrace);
-        Ok(CoroutineDefinitionNode { name: coroutine_name, block, doc })
+        Ok(CoroutineDefinitionNode { name: coroutine_name, yield_type, block, doc })
     }
 
     fn parse_statements(&mut self, block_type: BlockType) -> BlockNode {
@@ -354,7 +455,7 @@ impl Parser {
                 Ok(node) => statements.push(node),
                 Err(e) => {
                     self.errors.push(e);
-                    break;
+                    self.synchronize();
                 },
             }
             self.doc_comment = String::new();
@@ -369,10 +470,11 @@ impl Parser {
     fn parse_statement(&mut self, token: Token) -> Result<PrivateNode, ErrorContext> {
         match token.kind {
             TokenKind::Identifier(name) => self.parse_identifier(name),
+            TokenKind::Label(label) => self.parse_labeled_loop(label),
             TokenKind::ControlKeyword(keyword) => {
                 match keyword {
                     ControlKeyword::If => self.parse_if_statement(),
-                    ControlKeyword::While => self.parse_loop_statement(),
+                    ControlKeyword::While => self.parse_loop_statement(None),
 
                     _ => return Err(ErrorContext::new(
                         ErrorCode::Parse002,
@@ -384,14 +486,8 @@ impl Parser {
             TokenKind::DeclarationKeyword(keyword) => self.parse_declaration_keyword(keyword, token.row, token.col),
             TokenKind::FunctionControl(keyword) => {
                 if !self.block_stack.contains(&BlockType::Function) {
-                    self.errors.push(ErrorContext::new(
-                        ErrorCode::Parse006,
-                        Some(token.row), Some(token.col),
-                        vec![
-                            ("statement", &keyword.to_string()),
-                            ("block", "function"),
-                        ],
-                    ))
+                    let context = if self.block_stack.contains(&BlockType::Coroutine) { "コルーチン内" } else { "グローバルスコープ" };
+                    self.errors.push(Self::outside_function_error(&keyword.to_string(), context, token.row, token.col));
                 }
                 match keyword {
                     FunctionControl::Return => {
@@ -423,11 +519,25 @@ impl Parser {
                 }
                 
                 self.next_token()?;
+                let label = match self.peek_token()?.kind {
+                    TokenKind::Label(label) => {
+                        let label_token = self.next_token()?;
+                        if !self.label_stack.contains(&label) {
+                            self.errors.push(ErrorContext::new(
+                                ErrorCode::Parse008,
+                                Some(label_token.row), Some(label_token.col),
+                                vec![("label", &label)],
+                            ))
+                        }
+                        Some(label)
+                    },
+                    _ => None,
+                };
                 self.check_next_token(TokenKind::Semicolon);
 
                 let node = match keyword {
-                    LoopControl::Break => PrivateNode::Break,
-                    LoopControl::Continue => PrivateNode::Continue,
+                    LoopControl::Break => PrivateNode::Break { label },
+                    LoopControl::Continue => PrivateNode::Continue { label },
                 };
 
                 Ok(node)
@@ -437,17 +547,9 @@ impl Parser {
 
                 match keyword {
                     CoroutineControl::Resume => {
-                        let name_token = self.next_token()?;
-                        let task_name = match name_token.kind {
-                            TokenKind::Identifier(name) => name,
-                            _ => return Err(ErrorContext::new(
-                                ErrorCode::Parse005,
-                                Some(name_token.row), Some(name_token.col),
-                                vec![],
-                            )),
-                        };
+                        let resume = self.parse_resume()?;
                         self.check_next_token(TokenKind::Semicolon);
-                        Ok(PrivateNode::CoroutineResume { task_name })
+                        Ok(resume)
                     },
                     CoroutineControl::Yield => {
                         if !self.block_stack.contains(&BlockType::Coroutine) {
@@ -460,12 +562,22 @@ impl Parser {
                                 ],
                             ))
                         }
+                        let value = match self.peek_token()?.kind {
+                            TokenKind::Semicolon => None,
+                            _ => Some(Box::new(self.parse_assignable()?)),
+                        };
                         self.check_next_token(TokenKind::Semicolon);
-                        Ok(PrivateNode::Yield)
+                        Ok(PrivateNode::Yield { value })
                     },
                 }
             },
 
+            TokenKind::StringLiteral(_) | TokenKind::NumberLiteral(_) | TokenKind::BoolLiteral(_)
+            | TokenKind::ArithmeticOperator(Arithmetic::Plus|Arithmetic::Minus) | TokenKind::LParen
+            | TokenKind::LBracket | TokenKind::LogicalOperator(Logical::Unary(UnaryLogical::Not)) => {
+                self.parse_expression_statement()
+            },
+
             _ => return Err(ErrorContext::new(
                 ErrorCode::Parse002,
                 Some(token.row), Some(token.col),
@@ -474,6 +586,15 @@ impl Parser {
         }
     }
 
+    /// 式文の構文解析（評価結果は破棄される）
+    fn parse_expression_statement(&mut self) -> Result<PrivateNode, ErrorContext> {
+        let expression = self.parse_assignable()?;
+        self.check_next_token(TokenKind::Semicolon);
+        Ok(PrivateNode::ExpressionStatement {
+            expression: Box::new(expression),
+        })
+    }
+
     fn parse_if_statement(&mut self) -> Result<PrivateNode, ErrorContext> {
         self.next_token()?;
 
@@ -519,7 +640,23 @@ impl Parser {
         })
     }
 
-    fn parse_loop_statement(&mut self) -> Result<PrivateNode, ErrorContext> {
+    /// ラベル付きループ（`'label: while`）の構文解析
+    fn parse_labeled_loop(&mut self, label: String) -> Result<PrivateNode, ErrorContext> {
+        self.next_token()?;  // ラベルをスキップ
+        self.check_next_token(TokenKind::Colon);
+
+        let token = self.peek_token()?;
+        match token.kind {
+            TokenKind::ControlKeyword(ControlKeyword::While) => self.parse_loop_statement(Some(label)),
+            _ => Err(ErrorContext::new(
+                ErrorCode::Parse005,
+                Some(token.row), Some(token.col),
+                vec![("token", "while")],
+            )),
+        }
+    }
+
+    fn parse_loop_statement(&mut self, label: Option<String>) -> Result<PrivateNode, ErrorContext> {
         self.next_token()?;
         self.check_next_token(TokenKind::LParen);
 
@@ -536,19 +673,50 @@ impl Parser {
         self.check_next_token(TokenKind::LBrace);
 
         self.push_block(BlockType::Loop);
+        if let Some(label) = &label {
+            self.label_stack.push(label.clone());
+        }
         let block = self.parse_statements(BlockType::Loop);
+        if label.is_some() {
+            self.label_stack.pop();
+        }
         self.pop_block();
 
         self.check_next_token(TokenKind::RBrace);
 
+        // else節はループの外側として扱う（break, continueは外側のループが対象）
+        let else_block = match self.tokens.peek() {
+            Some(token) if token.kind == TokenKind::ControlKeyword(ControlKeyword::Else) => {
+                self.next_token()?;
+                self.check_next_token(TokenKind::LBrace);
+
+                self.push_block(BlockType::Conditional);
+                let else_block = self.parse_statements(BlockType::Conditional);
+                self.pop_block();
+
+                self.check_next_token(TokenKind::RBrace);
+
+                Some(else_block)
+            },
+            _ => None,
+        };
+
         Ok(PrivateNode::LoopStatement {
+            label,
             condition_node: Box::new(condition_node),
             block: block,
+            else_block,
         })
     }
 
     fn parse_identifier(&mut self, name: String) -> Result<PrivateNode, ErrorContext> {
-        self.next_token()?;  // 変数名または関数名のトークンをスキップスキップ
+        let next_token = self.peek_n(1)?;
+        if !matches!(next_token.kind, TokenKind::LParen | TokenKind::Equal) {
+            return self.parse_expression_statement()
+        }
+
+        let name_token = self.next_token()?;  // 変数名または関数名のトークンをスキップ
+        let (row, col) = (name_token.row, name_token.col);
         let token = self.next_token()?;
 
         match token.kind {
@@ -562,6 +730,8 @@ impl Parser {
                     name,
                     arguments,
                     return_flg: false,
+                    row,
+                    col,
                 });
             },
             TokenKind::Equal => {  // 変数と判定
@@ -572,6 +742,8 @@ impl Parser {
                 return Ok(PrivateNode::VariableAssignment {
                     name,
                     expression: Box::new(expression),
+                    row,
+                    col,
                 })
             },
             _ => {
@@ -586,8 +758,12 @@ impl Parser {
 
     fn parse_declaration_keyword(&mut self, keyword: DeclarationKeyword, row: u32, col: u32) -> Result<PrivateNode, ErrorContext> {
         match keyword {
-            DeclarationKeyword::Let => {
+            DeclarationKeyword::Let | DeclarationKeyword::Const => {
                 self.next_token()?;
+                let is_constant = keyword == DeclarationKeyword::Const;
+                if !is_constant && self.peek_token()?.kind == TokenKind::LBracket {
+                    return self.parse_array_destructuring()
+                }
                 let name_token = self.next_token()?;
                 let name = match name_token.kind {
                     TokenKind::Identifier(name) => name,
@@ -601,19 +777,23 @@ impl Parser {
                     }
                 };
 
-                self.check_next_token(TokenKind::Colon);
-
-                let type_token = self.next_token()?;
-                let variable_type = match type_token.kind {
-                    TokenKind::TypeName(type_name) => Type::from_keyword(&type_name),
-                    _ => {
-                        self.errors.push(ErrorContext::new(
-                            ErrorCode::Parse005,
-                            Some(name_token.row), Some(name_token.col),
-                            vec![("token", "型")],
-                        ));
-                        Type::Bool
-                    },
+                // 型を省略した場合は初期値から推論する
+                let variable_type = if self.peek_token()?.kind == TokenKind::Colon {
+                    self.next_token()?;
+                    let type_token = self.next_token()?;
+                    match type_token.kind {
+                        TokenKind::TypeName(type_name) => Some(self.parse_array_type(Type::from_keyword(&type_name))),
+                        _ => {
+                            self.errors.push(ErrorContext::new(
+                                ErrorCode::Parse005,
+                                Some(name_token.row), Some(name_token.col),
+                                vec![("token", "型")],
+                            ));
+                            Some(Type::Bool)
+                        },
+                    }
+                } else {
+                    None
                 };
 
                 let next_token = self.next_token()?;
@@ -633,12 +813,22 @@ impl Parser {
                         None
                     }
                 };
+                if variable_type.is_none() && initializer.is_none() {
+                    self.errors.push(ErrorContext::new(
+                        ErrorCode::Parse013,
+                        Some(name_token.row), Some(name_token.col),
+                        vec![("name", &name)],
+                    ));
+                }
 
                 return Ok(PrivateNode::VariableDeclaration {
                     name: name.to_string(),
                     variable_type,
                     initializer,
+                    is_constant,
                     doc: self.get_doc_comment(),
+                    row: name_token.row,
+                    col: name_token.col,
                 });
             },
             DeclarationKeyword::Coro => {
@@ -664,7 +854,7 @@ impl Parser {
                         self.check_next_token(TokenKind::LParen);
                         self.check_next_token(TokenKind::RParen);
                         self.check_next_token(TokenKind::Semicolon);
-                        Ok(PrivateNode::CoroutineInstantiation { task_name, coroutine_name })
+                        Ok(PrivateNode::CoroutineInstantiation { task_name, coroutine_name, row: token.row, col: token.col })
                     },
                     _ => {
                         Err(ErrorContext::new(
@@ -747,30 +937,16 @@ impl Parser {
         let token = self.peek_token()?;
         match token.kind.clone() {
             TokenKind::StringLiteral(_) | TokenKind::NumberLiteral(_) | TokenKind::BoolLiteral(_) 
-            | TokenKind::ArithmeticOperator(Arithmetic::Plus|Arithmetic::Minus) | TokenKind::LParen => {
+            | TokenKind::ArithmeticOperator(Arithmetic::Plus|Arithmetic::Minus) | TokenKind::LParen
+            | TokenKind::LogicalOperator(Logical::Unary(UnaryLogical::Not)) => {
                 return self.parse_expression();
             },
-            TokenKind::Identifier(name) => {
-                let next_token = self.peek_n(1)?;
-                match next_token.kind {
-                    TokenKind::LParen => {
-                        self.next_token()?;
-                        self.next_token()?;
-                        let arguments = self.parse_argument();
-                        
-                        self.check_next_token(TokenKind::RParen);
-                        
-                        return Ok(PrivateNode::FunctionCall {
-                            name,
-                            arguments,
-                            return_flg: true,
-                        });
-                    },
-                    _ => {
-                        return self.parse_expression();
-                    }
-                }
+            TokenKind::LBracket => self.parse_array_literal(),
+            TokenKind::CoroutineControl(CoroutineControl::Resume) => {
+                self.next_token()?;
+                self.parse_resume()
             },
+            TokenKind::Identifier(_) => self.parse_expression(),
             _ => Err(ErrorContext::new(
                 ErrorCode::Parse002,
                 Some(token.row), Some(token.col),
@@ -781,7 +957,21 @@ impl Parser {
 
     /// 式の構文解析
     fn parse_expression(&mut self) -> Result<PrivateNode, ErrorContext> {
-        self.parse_logical()
+        let condition = self.parse_logical()?;
+        if self.peek_token()?.kind != TokenKind::Question {
+            return Ok(condition)
+        }
+
+        // 条件式は最も優先度が低く、右結合
+        self.next_token()?;
+        let then_value = self.parse_assignable()?;
+        self.check_next_token(TokenKind::Colon);
+        let else_value = self.parse_assignable()?;
+        Ok(PrivateNode::Conditional {
+            condition: Box::new(condition),
+            then_value: Box::new(then_value),
+            else_value: Box::new(else_value),
+        })
     }
 
     /// 論理演算の構文解析
@@ -840,7 +1030,8 @@ impl Parser {
         let token = self.peek_token()?;
         match token.kind {
             TokenKind::LogicalOperator(Logical::Unary(UnaryLogical::Not)) => {
-                let value = self.parse_expression()?;
+                self.next_token()?;
+                let value = self.parse_not_expr()?;
                 Ok(PrivateNode::Logical {
                     operator: Logical::Unary(UnaryLogical::Not),
                     left: Box::new(value),
@@ -863,6 +1054,23 @@ impl Parser {
         
         self.next_token()?;
         let right = self.parse_value()?;
+
+        // `a < b < c`は比較の結果（bool）と値の比較になるため、構文エラーにする
+        let next_token = self.peek_token()?;
+        if let TokenKind::CompareOperator(next_operator) = next_token.kind {
+            self.errors.push(ErrorContext::new(
+                ErrorCode::Parse012,
+                Some(next_token.row), Some(next_token.col),
+                vec![
+                    ("left_operator", &operator.to_string()),
+                    ("operator", &next_operator.to_string()),
+                ],
+            ));
+            // 続く値を読み飛ばして解析を続ける
+            self.next_token()?;
+            self.parse_value()?;
+        }
+
         return Ok(PrivateNode::Compare {
             operator,
             left: Box::new(left?),
@@ -874,11 +1082,11 @@ impl Parser {
     fn parse_value(&mut self) -> Result<PrivateNode, ErrorContext> {
         let token = self.peek_token()?;
         match token.kind {
-            TokenKind::NumberLiteral(_) | TokenKind::ArithmeticOperator(Arithmetic::Plus|Arithmetic::Minus)
+            TokenKind::NumberLiteral(_) | TokenKind::StringLiteral(_)
+            | TokenKind::ArithmeticOperator(Arithmetic::Plus|Arithmetic::Minus)
             | TokenKind::LParen | TokenKind::Identifier(_) => {
                 return self.parse_add_and_sub()
             },
-            TokenKind::StringLiteral(_) => return self.parse_literal(),
             _ => Err(ErrorContext::new(
                 ErrorCode::Parse002,
                 Some(token.row), Some(token.col),
@@ -887,10 +1095,10 @@ impl Parser {
         }
     }
 
-    /// 足し算、引き算の構文解析
+    /// 足し算、引き算、文字列の連結の構文解析
     fn parse_add_and_sub(&mut self) -> Result<PrivateNode, ErrorContext> {
         let mut left = self.parse_mul_and_div()?;
-        while let Some(TokenKind::ArithmeticOperator(Arithmetic::Plus|Arithmetic::Minus)) = self.tokens.peek().map(|t| &t.kind) {
+        while let Some(TokenKind::ArithmeticOperator(Arithmetic::Plus|Arithmetic::Minus|Arithmetic::Concat)) = self.tokens.peek().map(|t| &t.kind) {
             let operator = match self.next_token()?.kind {
                 TokenKind::ArithmeticOperator(op) => op,
                 _ => unreachable!(),
@@ -913,7 +1121,6 @@ impl Parser {
                 TokenKind::ArithmeticOperator(op) => op,
                 _ => unreachable!(),
             };
-            self.next_token()?;
             let right = self.parse_unary()?;
             left = PrivateNode::Arithmetic {
                 operator: operator,
@@ -928,7 +1135,7 @@ impl Parser {
     fn parse_unary(&mut self) -> Result<PrivateNode, ErrorContext> {
         let token = self.peek_token()?;
         match token.kind {
-            TokenKind::NumberLiteral(_) | TokenKind::LParen | TokenKind::Identifier(_)
+            TokenKind::NumberLiteral(_) | TokenKind::StringLiteral(_) | TokenKind::LParen | TokenKind::Identifier(_)
             | TokenKind::ArithmeticOperator(Arithmetic::Plus)=> {
                 return self.parse_primary()
             },
@@ -949,11 +1156,11 @@ impl Parser {
         }
     }
 
-    /// 数値、計算式の'()'の構文解析
+    /// 数値、文字列、計算式の'()'の構文解析
     fn parse_primary(&mut self) -> Result<PrivateNode, ErrorContext> {
         let token = self.peek_token()?;
         match token.kind{
-            TokenKind::NumberLiteral(_) => return self.parse_literal(),
+            TokenKind::NumberLiteral(_) | TokenKind::StringLiteral(_) => return self.parse_literal(),
             TokenKind::LParen => {
                 self.next_token()?;
                 let expr = self.parse_add_and_sub();
@@ -961,7 +1168,13 @@ impl Parser {
                 self.check_next_token(TokenKind::RParen);
                 return expr;
             },
-            TokenKind::Identifier(_) => return self.parse_variable(),
+            TokenKind::Identifier(name) => {
+                if self.peek_n(1)?.kind == TokenKind::LParen {
+                    return self.parse_function_call_value(name, token.row, token.col)
+                }
+                let variable = self.parse_variable()?;
+                return self.parse_index(variable)
+            },
             _ => Err(ErrorContext::new(
                 ErrorCode::Parse002,
                 Some(token.row), Some(token.col),
@@ -970,11 +1183,40 @@ impl Parser {
         }
     }
 
+    /// 値として使う関数呼び出しの構文解析
+    fn parse_function_call_value(&mut self, name: String, row: u32, col: u32) -> Result<PrivateNode, ErrorContext> {
+        self.next_token()?;  // 関数名をスキップ
+        self.next_token()?;  // `(`をスキップ
+        let arguments = self.parse_argument();
+        self.check_next_token(TokenKind::RParen);
+
+        Ok(PrivateNode::FunctionCall {
+            name,
+            arguments,
+            return_flg: true,
+            row,
+            col,
+        })
+    }
+
+    /// `resume`に続くタスク名の構文解析
+    fn parse_resume(&mut self) -> Result<PrivateNode, ErrorContext> {
+        let name_token = self.next_token()?;
+        match name_token.kind {
+            TokenKind::Identifier(task_name) => Ok(PrivateNode::CoroutineResume { task_name }),
+            _ => Err(ErrorContext::new(
+                ErrorCode::Parse005,
+                Some(name_token.row), Some(name_token.col),
+                vec![("token", "タスク名")],
+            )),
+        }
+    }
+
     /// 変数呼び出しの構文解析
     fn parse_variable(&mut self) -> Result<PrivateNode, ErrorContext> {
         let token = self.next_token()?;
         match token.kind {
-            TokenKind::Identifier(name) => Ok(PrivateNode::Variable { name }),
+            TokenKind::Identifier(name) => Ok(PrivateNode::Variable { name, row: token.row, col: token.col }),
             _ => Err(ErrorContext::new(
                 ErrorCode::Parse002,
                 Some(token.row), Some(token.col),
@@ -983,6 +1225,119 @@ impl Parser {
         }
     }
 
+    /// 配列リテラルの構文解析
+    fn parse_array_literal(&mut self) -> Result<PrivateNode, ErrorContext> {
+        self.next_token()?;  // `[`をスキップ
+
+        let mut elements = Vec::new();
+        loop {
+            let token = self.peek_token()?;
+            if token.kind == TokenKind::RBracket { break; }
+
+            elements.push(self.parse_assignable()?);
+
+            let token = self.peek_token()?;
+            match token.kind {
+                TokenKind::Comma => { self.next_token()?; },
+                TokenKind::RBracket => break,
+                _ => return Err(ErrorContext::new(
+                    ErrorCode::Parse002,
+                    Some(token.row), Some(token.col),
+                    vec![("token", &token.kind.to_string())],
+                )),
+            }
+        }
+        self.check_next_token(TokenKind::RBracket);
+
+        Ok(PrivateNode::ArrayLiteral { elements })
+    }
+
+    /// 配列の要素の呼び出し（`arr[i]`）の構文解析
+    fn parse_index(&mut self, array: PrivateNode) -> Result<PrivateNode, ErrorContext> {
+        let mut node = array;
+        while let Some(TokenKind::LBracket) = self.tokens.peek().map(|t| &t.kind) {
+            self.next_token()?;
+            let index = self.parse_expression()?;
+            self.check_next_token(TokenKind::RBracket);
+            node = PrivateNode::Index {
+                array: Box::new(node),
+                index: Box::new(index),
+            };
+        }
+        Ok(node)
+    }
+
+    /// 配列の分割代入の構文解析（`let`の直後から）
+    /// 
+    /// ```txt
+    /// let [a, b, c]: int[] = array;
+    /// ```
+    fn parse_array_destructuring(&mut self) -> Result<PrivateNode, ErrorContext> {
+        let bracket_token = self.next_token()?;
+        let mut names = Vec::new();
+        loop {
+            let name_token = self.next_token()?;
+            match name_token.kind {
+                TokenKind::Identifier(name) => names.push(name),
+                _ => {
+                    return Err(ErrorContext::new(
+                        ErrorCode::Parse005,
+                        Some(name_token.row), Some(name_token.col),
+                        vec![("token", "変数名")],
+                    ))
+                },
+            }
+            let token = self.next_token()?;
+            match token.kind {
+                TokenKind::Comma => {},
+                TokenKind::RBracket => break,
+                _ => {
+                    return Err(ErrorContext::new(
+                        ErrorCode::Parse005,
+                        Some(token.row), Some(token.col),
+                        vec![("token", "]")],
+                    ))
+                },
+            }
+        }
+
+        self.check_next_token(TokenKind::Colon);
+        let type_token = self.next_token()?;
+        let variable_type = match type_token.kind {
+            TokenKind::TypeName(type_name) => self.parse_array_type(Type::from_keyword(&type_name)),
+            _ => {
+                return Err(ErrorContext::new(
+                    ErrorCode::Parse005,
+                    Some(type_token.row), Some(type_token.col),
+                    vec![("token", "型")],
+                ))
+            },
+        };
+
+        self.check_next_token(TokenKind::Equal);
+        let initializer = self.parse_assignable()?;
+        self.check_next_token(TokenKind::Semicolon);
+
+        Ok(PrivateNode::ArrayDestructuring {
+            names,
+            variable_type,
+            initializer: Box::new(initializer),
+            row: bracket_token.row,
+            col: bracket_token.col,
+        })
+    }
+
+    /// 型名に続く`[]`を読み取り、配列型に変換する
+    fn parse_array_type(&mut self, element_type: Type) -> Type {
+        let mut variable_type = element_type;
+        while let Some(TokenKind::LBracket) = self.tokens.peek().map(|t| &t.kind) {
+            let _ = self.next_token();
+            self.check_next_token(TokenKind::RBracket);
+            variable_type = Type::Array(Box::new(variable_type));
+        }
+        variable_type
+    }
+
     /// リテラル型の構文解析（String, Number, Bool）
     fn parse_literal(&mut self) -> Result<PrivateNode, ErrorContext> {
         let token = self.next_token()?;
@@ -990,34 +1345,22 @@ impl Parser {
             TokenKind::StringLiteral(value) => {
                 return Ok(PrivateNode::Literal { value: LiteralValue::String(value) });
             },
-            TokenKind::NumberLiteral(integer) => {
-                if let Ok(int_value) = integer.parse::<i32>() {
-                    let token = self.peek_token()?;
-                    match token.kind {
-                        TokenKind::Dot => {
-                            self.next_token()?;
-                            let mut number_string = integer.clone() + ".";
-                            let token = self.next_token()?;
-                            if let TokenKind::NumberLiteral(decimal) = token.kind {
-                                number_string.push_str(&decimal);
-                                if let Ok(float_value) = number_string.parse::<f64>() {
-                                    return Ok(PrivateNode::Literal { value: LiteralValue::Float(float_value) });
-                                }
-                            }
-                            return Err(ErrorContext::new(
-                                ErrorCode::Parse004,
-                                Some(token.row), Some(token.col),
-                                vec![("number", &number_string)],
-                            ))
-                        },
-                        _ => return Ok(PrivateNode::Literal { value: LiteralValue::Int(int_value) })
-                    }
+            TokenKind::NumberLiteral(number) => {
+                // 小数点や指数を含む数値は字句解析で1つのトークンになっている
+                let value = if number.contains(['.', 'e', 'E']) {
+                    number.parse::<f64>().ok().map(LiteralValue::Float)
                 } else {
-                    return Err(ErrorContext::new(
-                        ErrorCode::Parse004,
-                        Some(token.row), Some(token.col),
-                        vec![("number", &integer)],
-                    ))
+                    number.parse::<i32>().ok().map(LiteralValue::Int)
+                };
+                match value {
+                    Some(value) => return Ok(PrivateNode::Literal { value }),
+                    None => {
+                        return Err(ErrorContext::new(
+                            ErrorCode::Parse004,
+                            Some(token.row), Some(token.col),
+                            vec![("number", &number)],
+                        ))
+                    },
                 }
             },
             TokenKind::BoolLiteral(value) => {
@@ -1041,19 +1384,22 @@ impl Parser {
     fn parse_doc_comment(&mut self, token: &Token) -> Result<Option<PrivateNode>, ErrorContext> {
         if let TokenKind::DocComment(string) = &token.kind {
             self.next_token()?;
+            self.collect_todo(token);
             let re = Regex::new(r"@process(.*)").unwrap();
             if let Some(cap) = re.captures(string) {
                 let mut process_comment = cap[1].trim().to_string();
                 while let TokenKind::DocComment(string) = self.peek_token()?.kind {
                     process_comment = format!("{}\n{}", process_comment, string);
-                    self.next_token()?;
+                    let token = self.next_token()?;
+                    self.collect_todo(&token);
                 }
                 return Ok(Some(PrivateNode::ProcessComment { comment: process_comment }));
             } else {
                 let mut doc_comment = string.to_string();
                 while let TokenKind::DocComment(string) = self.peek_token()?.kind {
                     doc_comment = format!("{}\n{}", doc_comment, string);
-                    self.next_token()?;
+                    let token = self.next_token()?;
+                    self.collect_todo(&token);
                 }
                 self.doc_comment = doc_comment;
             }
@@ -1061,6 +1407,19 @@ impl Parser {
         Ok(None)
     }
 
+    /// Docコメントの行に`@todo`が含まれる場合、位置情報と合わせて記録する
+    fn collect_todo(&mut self, token: &Token) {
+        if let TokenKind::DocComment(string) = &token.kind {
+            if let Some(index) = string.find("@todo") {
+                self.todos.push(TodoNode {
+                    comment: string[index + "@todo".len()..].trim().to_string(),
+                    row: token.row,
+                    col: token.col,
+                });
+            }
+        }
+    }
+
     fn get_doc_comment(&mut self) -> Option<String> {
         if self.doc_comment == "" {
             None
@@ -1138,6 +1497,56 @@ impl Parser {
         }
     }
 
+    /// 構文エラーが発生した文を読み飛ばす
+    /// 
+    /// 次の`;`またはブロックの`}`の直後まで進める  
+    /// 外側のブロックを閉じる`}`とEOFは読み飛ばさない
+    fn synchronize(&mut self) {
+        let mut depth = 0;
+        while let Ok(token) = self.peek_token() {
+            match token.kind {
+                TokenKind::EOF => return,
+                TokenKind::RBrace if depth == 0 => return,
+                TokenKind::Semicolon if depth == 0 => {
+                    let _ = self.next_token();
+                    return
+                },
+                TokenKind::LBrace => {
+                    depth += 1;
+                    let _ = self.next_token();
+                },
+                TokenKind::RBrace => {
+                    depth -= 1;
+                    let _ = self.next_token();
+                    if depth == 0 { return }
+                },
+                _ => { let _ = self.next_token(); },
+            }
+        }
+    }
+
+    /// 関数外で`return`を使った場合のエラー
+    /// 
+    /// - `context` - 使われた場所（コルーチン内、グローバルスコープ）
+    fn outside_function_error(statement: &str, context: &str, row: u32, col: u32) -> ErrorContext {
+        ErrorContext::new(
+            ErrorCode::Parse011,
+            Some(row), Some(col),
+            vec![("statement", statement), ("context", context)],
+        )
+    }
+
+    /// グローバルで構文エラーが発生した場合、次の関数、コルーチンの定義またはインポートまで読み飛ばす
+    fn synchronize_global(&mut self) {
+        while let Ok(token) = self.peek_token() {
+            match token.kind {
+                TokenKind::EOF
+                | TokenKind::DeclarationKeyword(DeclarationKeyword::Function | DeclarationKeyword::Coroutine | DeclarationKeyword::Import) => return,
+                _ => { let _ = self.next_token(); },
+            }
+        }
+    }
+
     fn push_block(&mut self, block_type: BlockType) {
         self.block_stack.push(block_type);
         self.doc_comment = String::new();
@@ -1149,6 +1558,24 @@ impl Parser {
     }
 }
 
+/// `名前空間.名前`のトークン列を1つの識別子にまとめる
+fn merge_qualified_names(tokens: Vec<Token>) -> Vec<Token> {
+    let mut merged: Vec<Token> = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        if let (Some(Token { kind: TokenKind::Identifier(namespace), .. }), TokenKind::Dot, Some(Token { kind: TokenKind::Identifier(name), .. }))
+            = (merged.last_mut(), &tokens[i].kind, tokens.get(i + 1)) {
+            namespace.push('.');
+            namespace.push_str(name);
+            i += 2;
+            continue;
+        }
+        merged.push(tokens[i].clone());
+        i += 1;
+    }
+    merged
+}
+
 /// 構文解析を行う
 pub fn parse(tokens: Vec<Token>) -> (RootNode, Vec<ErrorContext>) {
     let mut parser = Parser::new(tokens);
@@ -0,0 +1,137 @@
+use std::fmt;
+use crate::common::types::LiteralValue;
+use super::node::*;
+
+/// 深さに応じたインデント
+fn indent(depth: usize) -> String {
+    "  ".repeat(depth)
+}
+
+impl RootNode {
+    /// ASTをインデントした木構造の文字列にする
+    pub fn print_tree(&self) -> String {
+        let mut tree = String::from("Root\n");
+        for import in &self.imports {
+            match &import.alias {
+                Some(alias) => tree.push_str(&format!("{}Import \"{}\" as {}\n", indent(1), import.path, alias)),
+                None => tree.push_str(&format!("{}Import \"{}\"\n", indent(1), import.path)),
+            }
+        }
+        for function in &self.functions {
+            tree.push_str(&function.print_tree(1));
+        }
+        for coroutine in &self.coroutines {
+            let yield_type = coroutine.yield_type.as_ref().map_or(String::new(), |t| format!(" -> {}", t.to_string()));
+            tree.push_str(&format!("{}Coroutine {}(){}\n", indent(1), coroutine.name, yield_type));
+            tree.push_str(&coroutine.block.print_tree(2));
+        }
+        tree
+    }
+}
+
+impl fmt::Display for RootNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.print_tree())
+    }
+}
+
+impl FunctionDefinitionNode {
+    pub fn print_tree(&self, depth: usize) -> String {
+        let parameters = self.parameters.iter()
+            .map(|param| format!("{}: {}", param.name, param.variable_type.to_string()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let return_type = self.return_type.as_ref().map_or(String::new(), |t| format!(" -> {}", t.to_string()));
+        let variadic = if self.is_variadic { " (variadic)" } else { "" };
+        let memoize = if self.memoize { " @memoize" } else { "" };
+
+        let mut tree = format!("{}Function {}({}){}{}{}\n", indent(depth), self.name, parameters, return_type, variadic, memoize);
+        tree.push_str(&self.block.print_tree(depth + 1));
+        tree
+    }
+}
+
+impl BlockNode {
+    pub fn print_tree(&self, depth: usize) -> String {
+        let mut tree = format!("{}Block ({})\n", indent(depth), self.block_type.to_string());
+        for statement in &self.statements {
+            tree.push_str(&statement.print_tree(depth + 1));
+        }
+        tree
+    }
+}
+
+impl PrivateNode {
+    pub fn print_tree(&self, depth: usize) -> String {
+        let (label, children, blocks) = self.tree_parts();
+        let mut tree = format!("{}{}\n", indent(depth), label);
+        for child in children {
+            tree.push_str(&child.print_tree(depth + 1));
+        }
+        for block in blocks {
+            tree.push_str(&block.print_tree(depth + 1));
+        }
+        tree
+    }
+
+    /// ノードの表示名と、子ノード、子ブロック
+    fn tree_parts(&self) -> (String, Vec<&PrivateNode>, Vec<&BlockNode>) {
+        match self {
+            Self::FunctionCall { name, arguments, .. } => (format!("FunctionCall {}", name), arguments.iter().collect(), vec![]),
+            Self::CoroutineInstantiation { task_name, coroutine_name, .. } => {
+                (format!("CoroutineInstantiation {} = {}()", task_name, coroutine_name), vec![], vec![])
+            },
+            Self::CoroutineResume { task_name } => (format!("CoroutineResume {}", task_name), vec![], vec![]),
+            Self::Yield { value } => ("Yield".to_string(), value.iter().map(|v| v.as_ref()).collect(), vec![]),
+            Self::VariableDeclaration { name, variable_type, initializer, is_constant, .. } => {
+                let keyword = if *is_constant { "const" } else { "let" };
+                let variable_type = variable_type.as_ref().map_or(String::new(), |t| format!(": {}", t.to_string()));
+                (
+                    format!("VariableDeclaration {} {}{}", keyword, name, variable_type),
+                    initializer.iter().map(|v| v.as_ref()).collect(),
+                    vec![],
+                )
+            },
+            Self::ArrayDestructuring { names, variable_type, initializer, .. } => {
+                (format!("ArrayDestructuring [{}]: {}", names.join(", "), variable_type.to_string()), vec![initializer], vec![])
+            },
+            Self::VariableAssignment { name, expression, .. } => (format!("VariableAssignment {}", name), vec![expression], vec![]),
+            Self::ExpressionStatement { expression } => ("ExpressionStatement".to_string(), vec![expression], vec![]),
+            Self::Variable { name, .. } => (format!("Variable {}", name), vec![], vec![]),
+            Self::IfStatement { condition_node, then_block, else_block } => {
+                let mut blocks = vec![then_block];
+                blocks.extend(else_block);
+                ("IfStatement".to_string(), vec![condition_node], blocks)
+            },
+            Self::LoopStatement { label, condition_node, block, else_block } => {
+                let label = label.as_ref().map_or(String::new(), |label| format!(" '{}", label));
+                let mut blocks = vec![block];
+                blocks.extend(else_block);
+                (format!("LoopStatement{}", label), vec![condition_node], blocks)
+            },
+            Self::ReturnStatement { assignalbe } => ("ReturnStatement".to_string(), vec![assignalbe], vec![]),
+            Self::ProcessComment { comment } => (format!("ProcessComment {:?}", comment), vec![], vec![]),
+            Self::Break { label } => (format!("Break{}", label.as_ref().map_or(String::new(), |l| format!(" '{}", l))), vec![], vec![]),
+            Self::Continue { label } => (format!("Continue{}", label.as_ref().map_or(String::new(), |l| format!(" '{}", l))), vec![], vec![]),
+            Self::Error => ("Error".to_string(), vec![], vec![]),
+            Self::Logical { operator, left, right } => {
+                let mut children = vec![left.as_ref()];
+                children.extend(right.as_deref());
+                (format!("Logical {}", operator.to_string()), children, vec![])
+            },
+            Self::Compare { operator, left, right } => (format!("Compare {}", operator.to_string()), vec![left, right], vec![]),
+            Self::Arithmetic { operator, left, right } => {
+                let mut children = vec![left.as_ref()];
+                children.extend(right.as_deref());
+                (format!("Arithmetic {}", operator.to_string()), children, vec![])
+            },
+            Self::Literal { value: LiteralValue::String(string) } => (format!("Literal {:?}", string), vec![], vec![]),
+            Self::Literal { value } => (format!("Literal {}", value.to_string()), vec![], vec![]),
+            Self::ArrayLiteral { elements } => ("ArrayLiteral".to_string(), elements.iter().collect(), vec![]),
+            Self::Index { array, index } => ("Index".to_string(), vec![array, index], vec![]),
+            Self::Conditional { condition, then_value, else_value } => {
+                ("Conditional".to_string(), vec![condition, then_value, else_value], vec![])
+            },
+        }
+    }
+}
@@ -1,54 +1,71 @@
 use serde::{Serialize, Deserialize};
 use serde_json::Value;
-use crate::{analyzer::semantic::Semantic, parser::node::*};
-use std::{
-    fs::File,
-    io::Write,
-    collections::HashMap,
+use crate::{
+    analyzer::{constant_folding::fold_constant, function_table::{FunctionTable, BUILTIN_FUNCTIONS}, semantic::Semantic},
+    common::types::LiteralValue,
+    error::{error_context::ErrorContext, error_message::ErrorMessage},
+    parser::node::*,
 };
+use std::collections::HashMap;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct JsonData {
     definitions: Vec<Definition>,
     structures: HashMap<String, Vec<Data>>,
+    /// 意味解析の警告（警告がない場合は出力しない）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    diagnostics: Vec<Diagnostic>,
 }
 impl JsonData {
-    pub fn new(semantic: Semantic) {
+    /// ## Argments
+    /// 
+    /// - `semantic` - 意味解析の結果
+    /// - `fold_constants` - リテラル引数による関数呼び出しに、畳み込んだ定数を付与するか
+    pub fn new(semantic: Semantic, fold_constants: bool) -> Self {
         let mut json_data = Self {
             definitions: Vec::new(),
             structures: HashMap::new(),
+            diagnostics: semantic.warnings.iter().map(Diagnostic::new).collect(),
         };
+        json_data.analyze(semantic, fold_constants);
+        json_data
+    }
 
-        let _ = json_data.ast_to_json(semantic);
+    /// シリアライズしたJSON文字列を返す
+    /// 
+    /// ## Argments
+    /// 
+    /// - `pretty` - インデントと改行を含めて整形するか（falseの場合は空白を含まない）
+    pub fn ast_to_json(&self, pretty: bool) -> serde_json::Result<String> {
+        if pretty {
+            serde_json::to_string_pretty(&self)
+        } else {
+            serde_json::to_string(&self)
+        }
     }
 
-    pub fn ast_to_json(&mut self, semantic: Semantic) -> serde_json::Result<()> {
+    fn analyze(&mut self, semantic: Semantic, fold_constants: bool) {
+        let function_table = semantic.function_table.clone();
         for (_, function_info) in semantic.function_table.table {
-            if function_info.name == "print" {
+            if BUILTIN_FUNCTIONS.contains(&function_info.name.as_str()) {
                 continue;
             }
 
-            self.definitions.push(Definition::new(&function_info.name, "function", &function_info.doc, ""));
-            let stack = AnalyzeAst::new(function_info.process);
+            // ローカル変数には引数も含まれる
+            let parameter_count = function_info.parameters.len();
+            let local_count = function_info.local_variables.variable_count() - parameter_count;
+            self.definitions.push(Definition::new(&function_info.name, "function", &function_info.doc, "", local_count, parameter_count));
+            let stack = AnalyzeAst::new(function_info.process, &function_table, fold_constants);
             self.structures.insert(function_info.name, stack);
         }
 
         for (_, task) in semantic.task_table.table {
             let coroutine = semantic.coroutine_table.get_coroutine_info(&task.coroutine_name).unwrap();
-            self.definitions.push(Definition::new(&task.task_name, "coroutine", &coroutine.doc, &coroutine.name));
-            let stack = AnalyzeAst::new(coroutine.process);
+            self.definitions.push(Definition::new(&task.task_name, "coroutine", &coroutine.doc, &coroutine.name, coroutine.local_variables.variable_count(), 0));
+            let stack = AnalyzeAst::new(coroutine.process, &function_table, fold_constants);
             self.structures.insert(task.task_name, stack);
         }
-
-        // シリアライズ
-        let serialized = serde_json::to_string_pretty(&self)?;
-    
-        let mut file = File::create("analyze.json").expect("Unable to create file");
-        writeln!(file, "{}", serialized).expect("Unable to write data");
-    
-        Ok(())
     }
-    
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -57,13 +74,52 @@ struct Definition {
     r#type: String,
     doc: String,
     r#ref: String,
+    /// 引数を除くローカル変数の数
+    local_count: usize,
+    parameter_count: usize,
 }
 impl Definition {
-    fn new(name: &str, r#type: &str, doc: &str, r#ref: &str) -> Self {
-        Self { name: name.to_string(), r#type: r#type.to_string(), doc: doc.to_string(), r#ref: r#ref.to_string() }
+    fn new(name: &str, r#type: &str, doc: &str, r#ref: &str, local_count: usize, parameter_count: usize) -> Self {
+        Self {
+            name: name.to_string(),
+            r#type: r#type.to_string(),
+            doc: doc.to_string(),
+            r#ref: r#ref.to_string(),
+            local_count,
+            parameter_count,
+        }
     }
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+struct Diagnostic {
+    code: String,
+    severity: String,
+    location: Option<Location>,
+    message: String,
+}
+impl Diagnostic {
+    fn new(error: &ErrorContext) -> Self {
+        let code = error.error_code.to_string();
+        let severity = if code.starts_with("WARNING") { "warning" } else { "error" };
+        let location = match (error.row, error.col) {
+            (Some(row), Some(col)) => Some(Location { row, col }),
+            _ => None,
+        };
+        // 位置はlocationに出力するため、メッセージには含めない
+        let message = ErrorMessage::global()
+            .get_error_message(ErrorContext { row: None, col: None, ..error.clone() })
+            .unwrap_or_else(|e| e);
+        Self { code, severity: severity.to_string(), location, message }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct Location {
+    row: u32,
+    col: u32,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 struct Data {
     r#type: String,
@@ -78,13 +134,17 @@ impl Data {
     }
 }
 
-struct AnalyzeAst {
+struct AnalyzeAst<'a> {
     stack: Vec<Data>,
+    function_table: &'a FunctionTable,
+    fold_constants: bool,
 }
-impl AnalyzeAst {
-    fn new(ast: BlockNode) -> Vec<Data> {
+impl<'a> AnalyzeAst<'a> {
+    fn new(ast: BlockNode, function_table: &'a FunctionTable, fold_constants: bool) -> Vec<Data> {
         let mut analyze_ast = Self{
             stack: Vec::new(),
+            function_table,
+            fold_constants,
         };
         analyze_ast.analyze_block(ast);
         return analyze_ast.stack;
@@ -96,28 +156,74 @@ impl AnalyzeAst {
         }
     }
 
+    /// ブロック内の要素を、現在の要素とは別に解析する（if文やループの入れ子の表現に使う）
+    fn analyze_nested_block(&mut self, block: BlockNode) -> Value {
+        let outer = std::mem::take(&mut self.stack);
+        self.analyze_block(block);
+        let nested = std::mem::replace(&mut self.stack, outer);
+        serde_json::to_value(nested).unwrap_or_default()
+    }
+
     fn analyze_node(&mut self, ast: PrivateNode) {
         match ast {
-            PrivateNode::IfStatement { condition_node:_, then_block, else_block } => {
-                self.analyze_block(then_block);
+            PrivateNode::IfStatement { condition_node, then_block, else_block } => {
+                let mut data = serde_json::json!({
+                    "condition": summarize(&condition_node),
+                    "then": self.analyze_nested_block(then_block),
+                });
                 if let Some(else_block) = else_block {
-                    self.analyze_block(else_block);
+                    data["else"] = self.analyze_nested_block(else_block);
                 }
+                self.stack.push(Data::new("if", data));
             },
-            PrivateNode::LoopStatement { condition_node:_, block } => {
-                self.analyze_block(block);
+            PrivateNode::LoopStatement { label, condition_node, block, else_block } => {
+                let mut data = serde_json::json!({
+                    "condition": summarize(&condition_node),
+                    "body": self.analyze_nested_block(block),
+                });
+                if let Some(label) = label {
+                    data["label"] = serde_json::json!(label);
+                }
+                if let Some(else_block) = else_block {
+                    data["else"] = self.analyze_nested_block(else_block);
+                }
+                self.stack.push(Data::new("while", data));
             },
 
-            PrivateNode::VariableDeclaration { name:_, variable_type:_, initializer, doc:_ } => {
+            PrivateNode::VariableDeclaration { name, variable_type, initializer, is_constant, .. } => {
+                let mut data = serde_json::json!({
+                    "name": &name,
+                    "variable_type": variable_type.map(|t| t.to_string()),
+                    "constant": is_constant,
+                });
+                if let Some(ini) = &initializer {
+                    data["initializer"] = serde_json::json!(summarize(ini));
+                }
+                self.stack.push(Data::new("variable_declaration", data));
                 if let Some(ini) = initializer {
                     self.analyze_node(*ini);
                 }
             },
-            PrivateNode::VariableAssignment { name:_, expression } => {
+            PrivateNode::ArrayDestructuring { names, variable_type, initializer, .. } => {
+                self.stack.push(Data::new(
+                    "variable_declaration",
+                    serde_json::json!({
+                        "names": &names,
+                        "variable_type": variable_type.to_string(),
+                        "constant": false,
+                        "initializer": summarize(&initializer),
+                    }),
+                ));
+                self.analyze_node(*initializer);
+            },
+            PrivateNode::VariableAssignment { name:_, expression, .. } => {
+                self.analyze_node(*expression);
+            },
+            PrivateNode::ExpressionStatement { expression } => {
                 self.analyze_node(*expression);
             },
 
-            PrivateNode::CoroutineInstantiation { task_name:_, coroutine_name:_ } => {},
+            PrivateNode::CoroutineInstantiation { task_name:_, coroutine_name:_, .. } => {},
             PrivateNode::CoroutineResume { task_name } => {
                 self.stack.push(Data::new(
                     "task_resume",
@@ -126,7 +232,7 @@ impl AnalyzeAst {
                     }),
                 ));
             },
-            PrivateNode::Yield => {
+            PrivateNode::Yield { value:_ } => {
                 self.stack.push(Data::new(
                     "yield",
                     serde_json::json!({
@@ -135,14 +241,15 @@ impl AnalyzeAst {
                 ));
             },
 
-            PrivateNode::FunctionCall { name, arguments, return_flg:_ } => {
-                if name != "print" {
-                    self.stack.push(Data::new(
-                        "function_call",
-                        serde_json::json!({
-                            "target": &name
-                        }),
-                    ));
+            PrivateNode::FunctionCall { name, arguments, return_flg:_, .. } => {
+                if !matches!(name.as_str(), "print" | "println" | "print_raw") {
+                    let mut data = serde_json::json!({
+                        "target": &name
+                    });
+                    if let Some(constants) = self.fold_arguments(&name, &arguments) {
+                        data["constants"] = constants;
+                    }
+                    self.stack.push(Data::new("function_call", data));
                 }
 
                 for arg in arguments {
@@ -162,4 +269,73 @@ impl AnalyzeAst {
             _ => {},
         }
     }
+
+    /// 全ての引数がリテラルに畳み込める場合、パラメータ名と値の対応を返す
+    fn fold_arguments(&self, name: &str, arguments: &Vec<PrivateNode>) -> Option<Value> {
+        if !self.fold_constants || BUILTIN_FUNCTIONS.contains(&name) {
+            return None
+        }
+        let function_info = self.function_table.get_function_info(name)?;
+
+        let mut constants = serde_json::Map::new();
+        for (param, arg) in function_info.parameters.iter().zip(arguments) {
+            let value = fold_constant(arg)?;
+            constants.insert(param.name.clone(), literal_to_json(&value));
+        }
+        Some(Value::Object(constants))
+    }
+}
+
+/// 式をソースコードに近い1行の文字列で表す（条件や初期値の概要に使う）
+fn summarize(node: &PrivateNode) -> String {
+    match node {
+        PrivateNode::Literal { value: LiteralValue::String(string) } => format!("{:?}", string),
+        PrivateNode::Literal { value } => value.to_string(),
+        PrivateNode::Variable { name, .. } => name.clone(),
+        PrivateNode::FunctionCall { name, arguments, .. } => {
+            format!("{}({})", name, arguments.iter().map(summarize).collect::<Vec<_>>().join(", "))
+        },
+        PrivateNode::ArrayLiteral { elements } => {
+            format!("[{}]", elements.iter().map(summarize).collect::<Vec<_>>().join(", "))
+        },
+        PrivateNode::Index { array, index } => format!("{}[{}]", summarize_operand(array), summarize(index)),
+        PrivateNode::Compare { operator, left, right } => {
+            format!("{} {} {}", summarize_operand(left), operator.to_string(), summarize_operand(right))
+        },
+        PrivateNode::Logical { operator, left, right: Some(right) } => {
+            format!("{} {} {}", summarize_operand(left), operator.to_string(), summarize_operand(right))
+        },
+        PrivateNode::Logical { operator, left, right: None } => format!("{} {}", operator.to_string(), summarize_operand(left)),
+        PrivateNode::Arithmetic { operator, left, right: Some(right) } => {
+            format!("{} {} {}", summarize_operand(left), operator.to_string(), summarize_operand(right))
+        },
+        PrivateNode::Arithmetic { operator, left, right: None } => format!("{}{}", operator.to_string(), summarize_operand(left)),
+        PrivateNode::Conditional { condition, then_value, else_value } => {
+            format!("{} ? {} : {}", summarize_operand(condition), summarize_operand(then_value), summarize_operand(else_value))
+        },
+        _ => String::new(),
+    }
+}
+
+/// 演算の被演算子の概要（演算や条件式は括弧で囲んで優先順位を明示する）
+fn summarize_operand(node: &PrivateNode) -> String {
+    match node {
+        PrivateNode::Compare { .. }
+        | PrivateNode::Logical { right: Some(_), .. }
+        | PrivateNode::Arithmetic { right: Some(_), .. }
+        | PrivateNode::Conditional { .. } => format!("({})", summarize(node)),
+        _ => summarize(node),
+    }
+}
+
+/// リテラル値をJSONの値へ変換
+fn literal_to_json(value: &LiteralValue) -> Value {
+    match value {
+        LiteralValue::Int(i) => serde_json::json!(i),
+        LiteralValue::Float(f) => serde_json::json!(f),
+        LiteralValue::Bool(b) => serde_json::json!(b),
+        LiteralValue::String(s) => serde_json::json!(s),
+        LiteralValue::Array(elements) => Value::Array(elements.iter().map(literal_to_json).collect()),
+        LiteralValue::Null => Value::Null,
+    }
 }
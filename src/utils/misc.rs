@@ -1,5 +1,21 @@
 use std::{fs::File, io::Read};
 
+/// ファイル読み込みのエラー
+#[derive(Debug)]
+pub enum LoadError {
+    /// ファイルを開けない、または読み取れない
+    Io(std::io::Error),
+    /// UTF-8として不正なバイト列を含む
+    /// 
+    /// `offset`は最初の不正なバイトの位置
+    InvalidUtf8 { offset: usize },
+}
+impl From<std::io::Error> for LoadError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
 /// fileの読み込み
 /// 
 /// ## Argments
@@ -21,11 +37,11 @@ use std::{fs::File, io::Read};
 ///     }
 /// };
 /// ```
-pub fn load_file_content(file_path: &str) -> Result<String, std::io::Error> {
+pub fn load_file_content(file_path: &str) -> Result<String, LoadError> {
     let mut file = File::open(file_path)?;
 
-    let mut content = String::new();
-    file.read_to_string(&mut content)?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
 
-    return Ok(content);
+    String::from_utf8(bytes).map_err(|e| LoadError::InvalidUtf8 { offset: e.utf8_error().valid_up_to() })
 }
@@ -0,0 +1,241 @@
+mod common;
+
+use common::{run_green, run_green_file, run_green_stdin, stderr, stdout};
+
+/// `-a`で解析結果のJSONを一時ファイルに出力し、その内容を返す
+fn analyze_json(name: &str, source: &str, args: &[&str]) -> String {
+    let json_path = common::temp_path(&format!("{}_json", name));
+    let mut args = args.to_vec();
+    args.extend(["-a", "-o", json_path.to_str().unwrap()]);
+    let output = run_green(name, source, &args);
+    assert!(output.status.success(), "{}", stdout(&output));
+    let json = std::fs::read_to_string(&json_path).unwrap();
+    let _ = std::fs::remove_file(&json_path);
+    json
+}
+
+#[test]
+fn debug_prints_only_with_flag() {
+    let source = "function main() {\n    debug(\"x\", 1);\n    println(\"done\");\n}\n";
+
+    let output = run_green("debug_on", source, &["--debug"]);
+    assert_eq!(stdout(&output), "done\n");
+    assert_eq!(stderr(&output), "x 1\n");
+
+    let output = run_green("debug_off", source, &[]);
+    assert_eq!(stdout(&output), "done\n");
+    assert_eq!(stderr(&output), "");
+}
+
+#[test]
+fn fold_constants_attaches_folded_arguments() {
+    let source = "function add(a: int, b: int) -> int {\n    return a + b;\n}\nfunction main() {\n    println(add(1, 2 * 3));\n}\n";
+    let json = analyze_json("fold_constants", source, &["--fold-constants", "--json-compact"]);
+    assert!(json.contains(r#"{"type":"function_call","data":{"constants":{"a":1,"b":6},"target":"add"}}"#), "{}", json);
+}
+
+#[test]
+fn dump_scopes_prints_nested_block_variables() {
+    let source = "function main() {\n    const x: int = 1;\n    if (x > 0) {\n        let y: int = 2;\n        println(y);\n    }\n}\n";
+    let output = run_green("dump_scopes", source, &["--dump-scopes"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "function main\n  scope #0 (parent: none)\n    x: int (const)\n      y: int\n");
+}
+
+#[test]
+fn eol_crlf_terminates_printed_lines() {
+    let source = "function main() {\n    println(\"a\");\n    print(\"b\");\n}\n";
+
+    let output = run_green("eol_crlf", source, &["--eol", "crlf"]);
+    assert_eq!(stdout(&output), "a\r\nb\r\n");
+
+    let output = run_green("eol_default", source, &[]);
+    assert_eq!(stdout(&output), "a\nb\n");
+}
+
+#[test]
+fn json_compact_and_pretty_analyze_output() {
+    let source = "function main() {\n}\n";
+
+    let compact = analyze_json("json_compact", source, &["--json-compact"]);
+    assert_eq!(
+        compact,
+        r#"{"definitions":[{"name":"main","type":"function","doc":"","ref":"","local_count":0,"parameter_count":0}],"structures":{"main":[]}}"#.to_string() + "\n",
+    );
+
+    let pretty = analyze_json("json_pretty", source, &["--json-pretty"]);
+    assert!(pretty.starts_with("{\n  \"definitions\": [\n    {\n      \"name\": \"main\","), "{}", pretty);
+    assert_eq!(analyze_json("json_default", source, &[]), pretty);
+}
+
+#[test]
+fn ast_hash_ignores_formatting_but_not_semantics() {
+    let ast_hash = |name: &str, source: &str| {
+        let output = run_green(name, source, &["--ast-hash"]);
+        assert!(output.status.success());
+        stdout(&output)
+    };
+    let original = ast_hash("ast_hash_original", "function main() {\n    println(1 + 2);\n}\n");
+    let reformatted = ast_hash("ast_hash_reformatted", "/// エントリポイント\nfunction main()\n{\n    // 足し算\n    println( 1+2 );\n}\n");
+    let changed = ast_hash("ast_hash_changed", "function main() {\n    println(1 + 3);\n}\n");
+
+    assert_eq!(original.len(), 17);
+    assert_eq!(original, reformatted);
+    assert_ne!(original, changed);
+}
+
+#[test]
+fn summary_counts_warnings_and_errors() {
+    let source = "function main() {\n    let unused: int = 1;\n    println(y);\n}\n";
+    let output = run_green("summary", source, &[]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).starts_with("警告: 使われていない変数: unused（main）\n 2行 9列目\n警告 1件, エラー 1件\n"), "{}", stderr(&output));
+}
+
+#[test]
+fn deny_warnings_counts_warnings_as_errors() {
+    let source = "function main() {\n    let unused: int = 1;\n}\n";
+
+    let output = run_green("warnings_allowed", source, &[]);
+    assert!(output.status.success());
+    assert!(stderr(&output).ends_with("警告 1件, エラー 0件\n"), "{}", stderr(&output));
+
+    let output = run_green("warnings_denied", source, &["--deny-warnings"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("警告 0件, エラー 1件\n"), "{}", stderr(&output));
+}
+
+#[test]
+fn invalid_utf8_source_reports_byte_offset() {
+    let path = common::temp_path("invalid_utf8");
+    std::fs::write(&path, b"function main() {\n    println(\"a\xff\");\n}\n").unwrap();
+    let output = run_green_file(&path, &[]);
+    let _ = std::fs::remove_file(&path);
+    assert!(!output.status.success());
+    let expected = format!("ファイルがUTF-8として不正: {}\n 不正なバイトの位置: 32\n警告 0件, エラー 1件\n", path.display());
+    assert!(stderr(&output).starts_with(&expected), "{}", stderr(&output));
+}
+
+#[test]
+fn analyze_output_counts_locals_and_parameters() {
+    let source = "function f(a: int, b: int) -> int {\n    let x: int = a;\n    let y: int = b;\n    let z: int = x + y;\n    return z;\n}\n\nfunction main() {\n    println(f(1, 2));\n}\n";
+    let json = analyze_json("local_count", source, &["--json-compact"]);
+    assert!(
+        json.contains(r#"{"name":"f","type":"function","doc":"","ref":"","local_count":3,"parameter_count":2}"#),
+        "{}", json,
+    );
+}
+
+#[test]
+fn fail_on_todo_rejects_remaining_todos() {
+    let source = "function main() {\n    /// @process 出力する\n    /// @todo 書式を整える\n    println(1);\n}\n";
+    let output = run_green("todo_remaining", source, &["--fail-on-todo"]);
+    assert!(!output.status.success());
+    assert_eq!(stdout(&output), "@todoが残っている: 書式を整える\n 3行 5列目\n");
+
+    let output = run_green("todo_ignored", source, &[]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "1\n");
+
+    let clean = "function main() {\n    /// @process 出力する\n    println(1);\n}\n";
+    let output = run_green("todo_clean", clean, &["--fail-on-todo"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "1\n");
+}
+
+#[test]
+fn analyze_output_includes_warning_diagnostics() {
+    let source = "function main() {\n    let unused: int = 1;\n}\n";
+    let json = analyze_json("diagnostics", source, &["--json-compact"]);
+    assert!(
+        json.contains(r#""diagnostics":[{"code":"WARNING001","severity":"warning","location":{"row":2,"col":9},"message":"警告: 使われていない変数: unused（main）"}]"#),
+        "{}", json,
+    );
+}
+
+#[test]
+fn repl_vars_lists_declared_variables() {
+    let output = run_green_stdin(&["--repl"], "let a: int = 1;\nlet b: string = \"x\";\n:vars\n");
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "> > > a: int = 1\nb: string = x\n> ");
+}
+
+#[test]
+fn failed_assertion_exits_with_dedicated_status() {
+    let source = "function main() {\n    assert(1 < 2);\n    assert_eq(1 + 1, 2);\n    println(\"ok\");\n    assert_eq(\"a\", \"b\");\n}\n";
+    let output = run_green("assert_failed", source, &[]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stdout(&output), "ok\n");
+    assert!(stderr(&output).contains("アサーションの失敗: 値が等しくない（左辺: a, 右辺: b）\n 5行 5列目"), "{}", stderr(&output));
+}
+
+#[test]
+fn stop_after_parse_skips_semantic_errors() {
+    let source = "function main() {\n    println(y);\n}\n";
+
+    let output = run_green("stop_after_parse", source, &["--stop-after", "parse"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "Root\n  Function main()\n    Block (Function)\n      FunctionCall println\n        Variable y\n");
+
+    let output = run_green("stop_after_analyze", source, &["--stop-after", "analyze"]);
+    assert!(!output.status.success());
+    assert_eq!(stdout(&output), "定義されていない変数の呼び出し: y\n 2行 13列目\n");
+}
+
+#[test]
+fn check_fails_without_main() {
+    let source = "function helper() {\n}\n";
+
+    let output = run_green("missing_main_check", source, &["--check"]);
+    assert!(!output.status.success());
+    assert_eq!(stdout(&output), "実行を開始する関数が定義されていない: main\n");
+
+    let json = analyze_json("missing_main_analyze", source, &[]);
+    assert!(json.contains("\"name\": \"helper\""), "{}", json);
+}
+
+#[test]
+fn float_precision_flag_is_applied() {
+    let output = run_green("float_precision", "function main() {\n    println(3.14159);\n}\n", &["--float-precision", "2"]);
+    assert_eq!(stdout(&output), "3.14\n");
+}
+
+#[test]
+fn dump_tokens_with_trivia_includes_comments_and_whitespace() {
+    let source = "function main() { // c\n    /* b */ println(1);\n}\n";
+    let output = run_green("trivia", source, &["--dump-tokens-with-trivia"]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        concat!(
+            "1:1 function\n1:9 Whitespace \" \"\n1:10 main\n1:14 (\n1:15 )\n1:16 Whitespace \" \"\n1:17 {\n",
+            "1:18 Whitespace \" \"\n1:19 Comment \"// c\"\n1:23 Whitespace \"\\n    \"\n",
+            "2:5 Comment \"/* b */\"\n2:12 Whitespace \" \"\n2:13 println\n2:20 (\n2:21 1\n2:22 )\n2:23 ;\n",
+            "2:24 Whitespace \"\\n\"\n3:1 }\n3:2 Whitespace \"\\n\"\n4:1 EOF\n",
+        ),
+    );
+}
+
+#[test]
+fn runtime_error_is_printed_before_summary() {
+    let source = "function main() {\n    println(to_string(1, \"x\"));\n}\n";
+    let output = run_green("runtime_error_summary", source, &[]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stderr(&output),
+        "実行エラー:\n int型に使えない書式: x\n int型のみ dec, hex, bin, oct を指定できます\n警告 0件, エラー 1件\n",
+    );
+}
+
+#[test]
+fn max_call_depth_is_bounded() {
+    let source = "function main() {\n    println(1);\n}\n";
+
+    let output = run_green("max_call_depth_in_range", source, &["--max-call-depth", "100"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "1\n");
+
+    let output = run_green("max_call_depth_out_of_range", source, &["--max-call-depth", "100000000"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("100000000 is not in 1..=100000"), "{}", stderr(&output));
+}
@@ -0,0 +1,26 @@
+mod common;
+
+use common::{run_green, stdout};
+
+const UNDEFINED_VARIABLE: &str = "function main() {\n    println(x);\n}\n";
+
+#[test]
+fn forced_color_wraps_code_and_location() {
+    let output = run_green("forced_color", UNDEFINED_VARIABLE, &["--color", "always"]);
+    assert_eq!(
+        stdout(&output),
+        "\x1b[31mSEMANTIC007\x1b[0m 定義されていない変数の呼び出し: x\n \x1b[36m2行 13列目\x1b[0m\n",
+    );
+}
+
+#[test]
+fn never_color_keeps_plain_message() {
+    let output = run_green("never_color", UNDEFINED_VARIABLE, &["--color", "never"]);
+    assert_eq!(stdout(&output), "定義されていない変数の呼び出し: x\n 2行 13列目\n");
+}
+
+#[test]
+fn no_color_overrides_auto() {
+    let output = run_green("no_color", UNDEFINED_VARIABLE, &["--no-color"]);
+    assert!(!stdout(&output).contains('\x1b'));
+}
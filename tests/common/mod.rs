@@ -0,0 +1,148 @@
+#![allow(dead_code)]
+
+use std::{io::Write, path::{Path, PathBuf}, process::{Command, Output, Stdio}};
+use green::{
+    analyzer::semantic::{self, Semantic},
+    error::{error_context::ErrorContext, error_message::ErrorMessage},
+    interpreter::{execute::{execute_with_writer, ExecuteOption}, output::{CaptureBuffer, LineEnding}},
+    lexer::lexical_analyzer,
+    parser::parser,
+};
+
+/// テストごとに異なる一時ファイルのパス
+pub fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("green_test_{}_{}.grn", std::process::id(), name))
+}
+
+/// ソースコードを一時ファイルに書き出し、`green`コマンドを実行する
+pub fn run_green(name: &str, source: &str, args: &[&str]) -> Output {
+    let path = temp_path(name);
+    std::fs::write(&path, source).unwrap();
+    let output = run_green_file(&path, args);
+    let _ = std::fs::remove_file(&path);
+    output
+}
+
+/// 既存のファイルを指定して`green`コマンドを実行する
+pub fn run_green_file(path: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_green"))
+        .args(args)
+        .arg(path)
+        .output()
+        .unwrap()
+}
+
+/// 標準入力を与えて`green`コマンドを実行する
+pub fn run_green_stdin(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_green"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+/// テストごとに異なる一時ディレクトリに、ファイル名と内容の組を書き出す
+pub fn write_temp_files(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("green_test_{}_{}", std::process::id(), name));
+    std::fs::create_dir_all(&dir).unwrap();
+    for (file_name, source) in files {
+        std::fs::write(dir.join(file_name), source).unwrap();
+    }
+    dir
+}
+
+/// 標準出力を文字列にする
+pub fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+/// 標準エラー出力を文字列にする
+pub fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+/// 実行時の設定の既定値
+pub fn default_option() -> ExecuteOption {
+    ExecuteOption {
+        debug: false,
+        max_output_bytes: None,
+        line_ending: LineEnding::Lf,
+        max_call_depth: 1000,
+        float_precision: None,
+    }
+}
+
+/// 字句解析から意味解析までを行う
+/// 
+/// 字句エラーと構文エラーがある場合はそのエラーを返す
+pub fn analyze(source: &str) -> Result<Semantic, Vec<ErrorContext>> {
+    let (tokens, errors) = lexical_analyzer::lex(source);
+    if !errors.is_empty() {
+        return Err(errors)
+    }
+    let (ast, errors) = parser::parse(tokens);
+    if !errors.is_empty() {
+        return Err(errors)
+    }
+    Ok(semantic::analyze(&ast))
+}
+
+/// 字句解析から意味解析までのエラーのエラーコード
+pub fn error_codes(source: &str) -> Vec<String> {
+    let errors = match analyze(source) {
+        Ok(semantic) => semantic.errors().to_vec(),
+        Err(errors) => errors,
+    };
+    errors.iter().map(|error| error.error_code.to_string()).collect()
+}
+
+/// 字句解析から意味解析までのエラーのメッセージ
+pub fn error_messages(source: &str) -> Vec<String> {
+    let errors = match analyze(source) {
+        Ok(semantic) => semantic.errors().to_vec(),
+        Err(errors) => errors,
+    };
+    errors.into_iter().map(|error| ErrorMessage::global().get_error_message(error).unwrap()).collect()
+}
+
+/// 意味解析の警告のエラーコード
+pub fn warning_codes(source: &str) -> Vec<String> {
+    let semantic = analyze(source).unwrap_or_else(|errors| panic!("解析エラー: {:?}", errors));
+    semantic.warnings.iter().map(|warning| warning.error_code.to_string()).collect()
+}
+
+/// エラーのないプログラムを実行し、出力と実行結果を返す
+pub fn run_with_option(source: &str, option: &ExecuteOption) -> (String, Result<(), String>) {
+    let semantic = analyze(source).unwrap_or_else(|errors| panic!("解析エラー: {:?}", errors));
+    if !semantic.errors().is_empty() {
+        let messages = semantic.errors().iter()
+            .map(|error| ErrorMessage::global().get_error_message(error.clone()).unwrap())
+            .collect::<Vec<_>>();
+        panic!("意味解析エラー: {:?}", messages);
+    }
+    let buffer = CaptureBuffer::default();
+    let result = execute_with_writer(&semantic, option, Box::new(buffer.clone()));
+    (buffer.contents(), result)
+}
+
+/// エラーのないプログラムを既定の設定で実行し、出力を返す
+pub fn run(source: &str) -> String {
+    let (output, result) = run_with_option(source, &default_option());
+    if let Err(e) = result {
+        panic!("実行エラー: {}\n出力: {}", e, output);
+    }
+    output
+}
+
+/// 実行時エラーになるプログラムを実行し、エラーメッセージを返す
+pub fn run_error(source: &str) -> String {
+    let (output, result) = run_with_option(source, &default_option());
+    match result {
+        Ok(()) => panic!("実行時エラーにならない\n出力: {}", output),
+        Err(e) => e,
+    }
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use common::{error_codes, run, run_error};
+
+#[test]
+fn coroutine_local_variable_is_readable() {
+    let source = "coroutine counter() -> int {\n    let i: int = 1;\n    yield i;\n    i = i + 1;\n    yield i;\n}\n\nfunction main() {\n    coro task = counter();\n    println(resume task);\n    println(resume task);\n}\n";
+    assert_eq!(error_codes(source), Vec::<String>::new());
+    assert_eq!(run(source), "1\n2\n");
+}
+
+#[test]
+fn yield_inside_loop_resumes_where_it_stopped() {
+    let source = r#"
+coroutine evens() -> int {
+    let i: int = 0;
+    while (i < 6) {
+        i = i + 1;
+        if (i == 2 or i == 4) {
+            yield i;
+        }
+    }
+    yield 100;
+}
+
+function main() {
+    coro task = evens();
+    println(resume task);
+    println(resume task);
+    println(resume task);
+}
+"#;
+    assert_eq!(run(source), "2\n4\n100\n");
+}
+
+#[test]
+fn step_runs_one_statement_at_a_time() {
+    let source = r#"
+coroutine hello() -> int {
+    print("A");
+    yield 1;
+    print("B");
+}
+
+function main() {
+    coro task = hello();
+    println(step(task));
+    println(step(task));
+    println(step(task));
+    println(step(task));
+}
+"#;
+    assert_eq!(run(source), "A\nrunning\npaused\nB\nrunning\ncompleted\n");
+}
+
+#[test]
+fn coroutine_can_resume_another_coroutine() {
+    let source = r#"
+coroutine inner() -> int {
+    yield 1;
+    yield 2;
+}
+
+coroutine outer() {
+    coro sub = inner();
+    let a: int = resume sub;
+    println("a", a);
+    yield;
+    println(resume sub);
+}
+
+function main() {
+    coro task = outer();
+    resume task;
+    println("main");
+    resume task;
+}
+"#;
+    assert_eq!(run(source), "a 1\nmain\n2\n");
+}
+
+#[test]
+fn resuming_a_running_task_is_an_error() {
+    let source = "coroutine selfish() {\n    resume me;\n}\n\nfunction main() {\n    coro me = selfish();\n    resume me;\n}\n";
+    assert_eq!(run_error(source), "実行中のタスクは再開できない: me\n タスクの再開が循環しています");
+}
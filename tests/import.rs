@@ -0,0 +1,67 @@
+mod common;
+
+use common::{run_green_file, stdout, write_temp_files};
+
+const LIB: &str = "function add(a: int, b: int) -> int {\n    return a + b;\n}\n";
+
+#[test]
+fn imported_function_can_be_called() {
+    let dir = write_temp_files("import_helper", &[
+        ("lib.grn", LIB),
+        ("main.grn", "import \"lib.grn\";\n\nfunction main() {\n    println(add(2, 3));\n}\n"),
+    ]);
+    let output = run_green_file(&dir.join("main.grn"), &[]);
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "5\n");
+}
+
+#[test]
+fn missing_import_is_reported() {
+    let dir = write_temp_files("import_missing", &[
+        ("main.grn", "import \"missing.grn\";\n\nfunction main() {\n}\n"),
+    ]);
+    let output = run_green_file(&dir.join("main.grn"), &[]);
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(!output.status.success());
+    assert_eq!(stdout(&output), "インポートするファイルが見つからない: missing.grn \n 1行 1列目\n");
+}
+
+#[test]
+fn cyclic_import_is_reported() {
+    let dir = write_temp_files("import_cycle", &[
+        ("a.grn", "import \"b.grn\";\n\nfunction main() {\n}\n"),
+        ("b.grn", "import \"a.grn\";\n"),
+    ]);
+    let output = run_green_file(&dir.join("a.grn"), &[]);
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(!output.status.success());
+    assert_eq!(stdout(&output), "循環インポート: a.grn \n 1行 1列目\n");
+}
+
+#[test]
+fn namespaced_call_and_same_named_local_function_do_not_collide() {
+    let dir = write_temp_files("import_namespace", &[
+        ("lib.grn", LIB),
+        ("main.grn", "import \"lib.grn\" as util;\n\nfunction add(a: int, b: int) -> int {\n    return a * b;\n}\n\nfunction main() {\n    println(add(2, 3), util.add(2, 3));\n}\n"),
+    ]);
+    let output = run_green_file(&dir.join("main.grn"), &[]);
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "6 5\n");
+}
+
+#[test]
+fn unknown_namespace_and_member_are_reported() {
+    let dir = write_temp_files("import_namespace_unknown", &[
+        ("lib.grn", LIB),
+        ("main.grn", "import \"lib.grn\" as util;\n\nfunction main() {\n    println(other.add(2, 3));\n    println(util.sub(2, 3));\n}\n"),
+    ]);
+    let output = run_green_file(&dir.join("main.grn"), &[]);
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(!output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "定義されていない名前空間: other\n 4行 13列目\n名前空間'util'に定義されていない関数の呼び出し: sub\n 5行 13列目\n",
+    );
+}
@@ -0,0 +1,249 @@
+mod common;
+
+use common::{default_option, run, run_error, run_with_option};
+use green::{
+    common::types::LiteralValue,
+    interpreter::{execute::{execute_with_writer, Interpreter}, output::CaptureBuffer, variable::VariableManager},
+};
+
+#[test]
+fn chars_and_from_chars_round_trip() {
+    let output = run(r#"
+function main() {
+    let letters: string[] = chars("abc");
+    println(letters, from_chars(letters));
+    println(from_chars(chars("abc")) == "abc");
+}
+"#);
+    assert_eq!(output, "[a, b, c] abc\ntrue\n");
+}
+
+#[test]
+fn runaway_printing_stops_at_max_output_bytes() {
+    let source = "function main() {\n    while (true) {\n        print(\"abc\");\n    }\n}\n";
+    let option = green::interpreter::execute::ExecuteOption {
+        max_output_bytes: Some(10),
+        ..default_option()
+    };
+    let (output, result) = run_with_option(source, &option);
+    assert_eq!(output, "abc\nabc\n");
+    assert_eq!(result, Err("出力サイズが上限（10バイト）を超えた".to_string()));
+}
+
+#[test]
+fn scope_depth_is_restored_after_calls() {
+    let output = run(r#"
+function count(n: int) -> int {
+    if (n == 0) {
+        return 0;
+    }
+    let rest: int = count(n - 1);
+    return rest + 1;
+}
+
+function main() {
+    println(count(3));
+}
+"#);
+    assert_eq!(output, "3\n");
+}
+
+#[test]
+fn scope_depth_mismatch_is_detected() {
+    let mut variable_manager = VariableManager::new();
+    let depth = variable_manager.depth();
+    variable_manager.push_scope();
+    assert_eq!(
+        variable_manager.check_depth(depth, "leak"),
+        Err("関数呼び出しの前後でスコープの深さが一致しない（関数: leak, 呼び出し前: 1, 呼び出し後: 2）".to_string()),
+    );
+    variable_manager.pop_scope();
+    assert_eq!(variable_manager.check_depth(depth, "leak"), Ok(()));
+}
+
+#[test]
+fn to_string_formats_ints_in_hex_and_binary() {
+    let output = run(r#"
+function main() {
+    println(to_string(255, "hex"), to_string(10, "bin"), to_string(-255, "hex"));
+}
+"#);
+    assert_eq!(output, "ff 1010 -ff\n");
+}
+
+#[test]
+fn to_string_rejects_unknown_format() {
+    let error = run_error("function main() {\n    println(to_string(1, \"base64\"));\n}\n");
+    assert!(error.contains("int型に使えない書式: base64"), "{}", error);
+}
+
+#[test]
+fn array_destructuring_binds_each_element() {
+    let output = run(r#"
+function main() {
+    let arr: int[] = [1, 2, 3];
+    let [a, b, c]: int[] = arr;
+    println(a, b, c);
+}
+"#);
+    assert_eq!(output, "1 2 3\n");
+}
+
+#[test]
+fn array_destructuring_length_mismatch_is_an_error() {
+    let error = run_error(r#"
+function main() {
+    let arr: int[] = [1, 2, 3];
+    let [a, b]: int[] = arr;
+    println(a, b);
+}
+"#);
+    assert_eq!(error, "分割代入する変数の個数と配列の長さの不一致（変数: 2, 要素: 3）");
+}
+
+#[test]
+fn memoized_fib_is_correct_and_cached() {
+    let output = run(r#"
+/// @memoize
+function fib(n: int) -> int {
+    if (n < 2) {
+        return n;
+    }
+    let a: int = fib(n - 1);
+    let b: int = fib(n - 2);
+    return a + b;
+}
+
+/// @memoize
+function square(n: int) -> int {
+    println("calc", n);
+    return n * n;
+}
+
+function main() {
+    println(fib(40));
+    println(square(3), square(3), square(4));
+}
+"#);
+    // メモ化しない場合、fib(40)は数億回の呼び出しになる
+    assert_eq!(output, "102334155\ncalc 3\ncalc 4\n9 9 16\n");
+}
+
+#[test]
+fn nested_calls_keep_caller_variables() {
+    let output = run(r#"
+function add(a: int, b: int) -> int {
+    let sum: int = a + b;
+    return sum;
+}
+
+function mul(a: int, b: int) -> int {
+    return a * b;
+}
+
+function square(a: int) -> int {
+    return mul(a, a);
+}
+
+function main() {
+    let a: int = 10;
+    let sum: int = 1;
+    println(add(mul(2, 3), square(add(1, 1))));
+    println(a, sum);
+}
+"#);
+    assert_eq!(output, "10\n10 1\n");
+}
+
+#[test]
+fn tilde_concatenates_with_numbers() {
+    let output = run("function main() {\n    println(\"x=\" ~ 5, 1.5 ~ \"!\");\n}\n");
+    assert_eq!(output, "x=5 1.5!\n");
+}
+
+#[test]
+fn print_without_arguments_emits_a_newline() {
+    assert_eq!(run("function main() {\n    print();\n}\n"), "\n");
+    assert_eq!(run("function main() {\n    println();\n}\n"), "\n");
+}
+
+#[test]
+fn nan_and_infinities_have_canonical_text() {
+    let output = run("function main() {\n    println(0.0 / 0.0, 1.0 / 0.0, -1.0 / 0.0);\n}\n");
+    assert_eq!(output, "nan inf -inf\n");
+}
+
+#[test]
+fn runtime_comparison_error_shows_bool_value_and_type() {
+    assert_eq!(LiteralValue::Bool(true).describe(), "true（bool）");
+    assert_eq!(LiteralValue::String("1".to_string()).describe(), "\"1\"（string）");
+
+    // 意味解析のエラーを無視して実行し、実行時のエラーメッセージを確認する
+    let semantic = common::analyze("function main() {\n    let flag: bool = true;\n    println(flag == 1);\n}\n").unwrap();
+    assert!(!semantic.errors().is_empty());
+    let result = execute_with_writer(&semantic, &default_option(), Box::new(CaptureBuffer::default()));
+    let error = result.unwrap_err();
+    assert!(error.contains("左: true（bool） 演算子: == 右: 1（int）"), "{}", error);
+}
+
+#[test]
+fn float_precision_rounds_printed_floats() {
+    let source = "function main() {\n    let pi: float = 3.14159;\n    println(pi, [pi], 2);\n}\n";
+    let option = green::interpreter::execute::ExecuteOption {
+        float_precision: Some(2),
+        ..default_option()
+    };
+    let (output, result) = run_with_option(source, &option);
+    assert_eq!(result, Ok(()));
+    assert_eq!(output, "3.14 [3.14] 2\n");
+    assert_eq!(run(source), "3.14159 [3.14159] 2\n");
+}
+
+#[test]
+fn int_literal_initializes_float_variable() {
+    let output = run("function main() {\n    let x: float = 1;\n    println(typeof(x), x / 2.0);\n}\n");
+    assert_eq!(output, "float 0.5\n");
+}
+
+#[test]
+fn print_output_is_captured_by_the_writer() {
+    let semantic = common::analyze("function main() {\n    print(\"a\");\n    println(1, 2.5);\n}\n").unwrap();
+
+    let buffer = CaptureBuffer::default();
+    assert_eq!(execute_with_writer(&semantic, &default_option(), Box::new(buffer.clone())), Ok(()));
+    assert_eq!(buffer.contents(), "a\n1 2.5\n");
+
+    // 同じインタプリタで続けて実行した出力も同じバッファに書き込まれる
+    let buffer = CaptureBuffer::default();
+    let mut interpreter = Interpreter::with_writer(&semantic, &default_option(), Box::new(buffer.clone()));
+    interpreter.execute_program().unwrap();
+    interpreter.execute_program().unwrap();
+    assert_eq!(buffer.contents(), "a\n1 2.5\na\n1 2.5\n");
+}
+
+#[test]
+fn chars_of_empty_string_round_trips() {
+    let output = run(r#"
+function main() {
+    let letters: string[] = chars("");
+    println(letters, from_chars(letters) == "");
+    println(from_chars(chars("")) == "");
+}
+"#);
+    assert_eq!(output, "[] true\ntrue\n");
+}
+
+#[test]
+fn variadic_function_accepts_zero_or_more_trailing_arguments() {
+    let output = run(r#"
+function show(label: string, xs: int...) {
+    println(label, xs);
+}
+
+function main() {
+    show("none");
+    show("many", 1, 2, 3);
+}
+"#);
+    assert_eq!(output, "none []\nmany [1, 2, 3]\n");
+}
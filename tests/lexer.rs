@@ -0,0 +1,131 @@
+mod common;
+
+use green::{
+    error::error_message::ErrorMessage,
+    lexer::{lexical_analyzer::{lex, lex_with_limit, lex_with_trivia}, token::TokenKind},
+};
+
+/// トークンの種類の一覧（終端を除く）
+fn kinds(source: &str) -> Vec<TokenKind> {
+    let (tokens, errors) = lex(source);
+    assert!(errors.is_empty(), "字句エラー: {:?}", errors);
+    tokens.into_iter()
+        .map(|token| token.kind)
+        .filter(|kind| *kind != TokenKind::EOF)
+        .collect()
+}
+
+#[test]
+fn unicode_identifiers_are_accepted() {
+    assert_eq!(
+        kinds("変数 _x1 café;"),
+        vec![
+            TokenKind::Identifier("変数".to_string()),
+            TokenKind::Identifier("_x1".to_string()),
+            TokenKind::Identifier("café".to_string()),
+            TokenKind::Semicolon,
+        ],
+    );
+}
+
+#[test]
+fn non_ascii_digit_in_identifier_is_rejected() {
+    let (_, errors) = lex("x１;");
+    let codes = errors.iter().map(|error| error.error_code.to_string()).collect::<Vec<_>>();
+    assert_eq!(codes, vec!["LEX006"]);
+    assert!(common::error_messages("function main() {\n    let x１: int = 1;\n}\n")[0].contains("識別子'x１'に使えない文字: １"));
+}
+
+#[test]
+fn escape_sequences_are_decoded() {
+    assert_eq!(
+        kinds(r#""line1\nline2\t\"q\"\\";"#),
+        vec![
+            TokenKind::StringLiteral("line1\nline2\t\"q\"\\".to_string()),
+            TokenKind::Semicolon,
+        ],
+    );
+}
+
+#[test]
+fn unknown_escape_sequence_is_rejected() {
+    let (_, errors) = lex(r#""a\qb";"#);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].error_code.to_string(), "LEX007");
+    assert_eq!((errors[0].row, errors[0].col), (Some(1), Some(3)));
+}
+
+#[test]
+fn nested_block_comments_are_skipped() {
+    assert_eq!(
+        kinds("a /* outer /* inner /* innermost */ */ still outer */ b;"),
+        vec![
+            TokenKind::Identifier("a".to_string()),
+            TokenKind::Identifier("b".to_string()),
+            TokenKind::Semicolon,
+        ],
+    );
+}
+
+#[test]
+fn unclosed_nested_block_comment_points_at_its_start() {
+    let (_, errors) = lex("a;\n  /* outer /* inner */ b;\n");
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].error_code.to_string(), "LEX004");
+    assert_eq!((errors[0].row, errors[0].col), (Some(2), Some(3)));
+}
+
+#[test]
+fn underscores_between_digits_are_stripped() {
+    assert_eq!(
+        kinds("1_000_000 3.141_592;"),
+        vec![
+            TokenKind::NumberLiteral("1000000".to_string()),
+            TokenKind::NumberLiteral("3.141592".to_string()),
+            TokenKind::Semicolon,
+        ],
+    );
+}
+
+#[test]
+fn misplaced_underscores_are_rejected() {
+    for source in ["1__000;", "1000_;", "3._5;"] {
+        let (_, errors) = lex(source);
+        let codes = errors.iter().map(|error| error.error_code.to_string()).collect::<Vec<_>>();
+        assert_eq!(codes, ["LEX009"], "{}", source);
+    }
+}
+
+#[test]
+fn over_long_identifier_and_string_are_rejected() {
+    let (_, errors) = lex_with_limit("abcdefghij \"0123456789\" abcdefghijk \"0123456789a\";", 10);
+    let messages = errors.into_iter()
+        .map(|error| ErrorMessage::global().get_error_message(error).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        [
+            "識別子が長すぎる（11文字, 上限: 10文字）\n 1行 25列目",
+            "文字列が長すぎる（11文字, 上限: 10文字）\n 1行 37列目",
+        ],
+    );
+}
+
+#[test]
+fn trivia_tokens_are_kept_only_on_request() {
+    let source = "a; // c\n/* b */";
+    let (tokens, errors) = lex_with_trivia(source, 65536);
+    assert!(errors.is_empty(), "字句エラー: {:?}", errors);
+    let comments = tokens.iter()
+        .filter_map(|token| match &token.kind {
+            TokenKind::Comment(text) => Some((text.as_str(), token.row, token.col)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(comments, [("// c", 1, 4), ("/* b */", 2, 1)]);
+
+    assert_eq!(
+        kinds(source),
+        vec![TokenKind::Identifier("a".to_string()), TokenKind::Semicolon],
+    );
+}
@@ -0,0 +1,164 @@
+mod common;
+
+use green::{
+    lexer::lexical_analyzer::lex,
+    common::{operator::Arithmetic, types::LiteralValue},
+    error::error_message::ErrorMessage,
+    parser::{node::{PrivateNode, RootNode}, normalize::{normalize, structurally_equal}, parser::parse},
+};
+
+/// 構文エラーのないソースコードを構文解析する
+fn parse_source(source: &str) -> RootNode {
+    let (tokens, errors) = lex(source);
+    assert!(errors.is_empty(), "字句エラー: {:?}", errors);
+    let (root, errors) = parse(tokens);
+    assert!(errors.is_empty(), "構文エラー: {:?}", errors);
+    root
+}
+
+/// 最初の関数の文の一覧
+fn first_statements(source: &str) -> Vec<PrivateNode> {
+    parse_source(source).functions.remove(0).block.statements
+}
+
+#[test]
+fn bare_expression_statement_is_parsed_and_discarded() {
+    let source = "function main() {\n    1 + 2;\n    println(\"ok\");\n}\n";
+    let statements = first_statements(source);
+    assert!(matches!(statements[0], PrivateNode::ExpressionStatement { .. }), "{:?}", statements[0]);
+    assert_eq!(common::run(source), "ok\n");
+}
+
+/// 変数宣言の初期値の式
+fn initializer(statement: PrivateNode) -> PrivateNode {
+    match statement {
+        PrivateNode::VariableDeclaration { initializer: Some(initializer), .. } => *initializer,
+        statement => panic!("初期値のある変数宣言ではない: {:?}", statement),
+    }
+}
+
+/// 左右の項を持つ算術演算のノード
+fn arithmetic(operator: Arithmetic, left: PrivateNode, right: PrivateNode) -> PrivateNode {
+    PrivateNode::Arithmetic { operator, left: Box::new(left), right: Some(Box::new(right)) }
+}
+
+fn int(value: i32) -> PrivateNode {
+    PrivateNode::Literal { value: LiteralValue::Int(value) }
+}
+
+fn variable(name: &str, row: u32, col: u32) -> PrivateNode {
+    PrivateNode::Variable { name: name.to_string(), row, col }
+}
+
+#[test]
+fn multiplication_chain_is_left_associative() {
+    let source = "function main() {\n    let x: int = 2 * 3 * 4;\n}\n";
+    let expected = arithmetic(Arithmetic::Multiply, arithmetic(Arithmetic::Multiply, int(2), int(3)), int(4));
+    assert_eq!(initializer(first_statements(source).remove(0)), expected);
+}
+
+#[test]
+fn division_then_multiplication_is_left_associative() {
+    let source = "function main() {\n    let a: int = 8;\n    let b: int = 2;\n    let c: int = 3;\n    let x: int = a / b * c;\n    println(x);\n}\n";
+    let expected = arithmetic(
+        Arithmetic::Multiply,
+        arithmetic(Arithmetic::Divide, variable("a", 5, 18), variable("b", 5, 22)),
+        variable("c", 5, 26),
+    );
+    assert_eq!(initializer(first_statements(source).remove(3)), expected);
+    assert_eq!(common::run(source), "12\n");
+}
+
+#[test]
+fn every_syntax_error_is_reported() {
+    let source = "function main() {\n    let a: int = ;\n    let b: int = 1 + ;\n    let c: int = * 2;\n    println(\"ok\");\n}\n";
+    let (tokens, _) = lex(source);
+    let (root, errors) = parse(tokens);
+    let positions = errors.iter()
+        .map(|error| (error.error_code.to_string(), error.row, error.col))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        positions,
+        vec![
+            ("PARSE002".to_string(), Some(2), Some(18)),
+            ("PARSE002".to_string(), Some(3), Some(22)),
+            ("PARSE002".to_string(), Some(4), Some(18)),
+        ],
+    );
+    // 構文エラーの後も解析を続け、後続の文を含むASTを返す
+    assert!(root.functions[0].block.statements.iter().any(|statement| matches!(
+        statement,
+        PrivateNode::FunctionCall { name, .. } if name == "println"
+    )));
+}
+
+#[test]
+fn equivalent_programs_are_equal_after_normalization() {
+    let compact = parse_source("function main() {\n    let x: int = 1 + 2;\n    println(x);\n}\n");
+    let spread = parse_source("/// エントリポイント\nfunction main()\n{\n\n    let x: int =\n        1 + 2;\n    println( x );\n}\n");
+    assert_ne!(compact, spread);
+    assert_eq!(normalize(&compact), normalize(&spread));
+    assert!(structurally_equal(&compact, &spread));
+
+    let different = parse_source("function main() {\n    let x: int = 2 + 1;\n    println(x);\n}\n");
+    assert!(!structurally_equal(&compact, &different));
+}
+
+fn float(value: f64) -> PrivateNode {
+    PrivateNode::Literal { value: LiteralValue::Float(value) }
+}
+
+#[test]
+fn leading_dot_float_parses_as_float() {
+    let source = "function main() {\n    let x: float = .5;\n}\n";
+    assert_eq!(initializer(first_statements(source).remove(0)), float(0.5));
+}
+
+#[test]
+fn trailing_dot_float_parses_without_cascading_errors() {
+    let source = "function main() {\n    let x: float = 5.;\n    println(x);\n}\n";
+    let statements = first_statements(source);
+    assert_eq!(statements.len(), 2);
+    assert_eq!(initializer(statements.into_iter().next().unwrap()), float(5.0));
+}
+
+#[test]
+fn omitted_integer_or_fraction_digits_make_floats() {
+    let source = "function main() {\n    let x: float = .5 + .5;\n    let y: float = 10.;\n}\n";
+    let mut statements = first_statements(source).into_iter();
+    assert_eq!(
+        initializer(statements.next().unwrap()),
+        arithmetic(Arithmetic::Plus, float(0.5), float(0.5)),
+    );
+    assert_eq!(initializer(statements.next().unwrap()), float(10.0));
+}
+
+/// 構文エラーのメッセージ
+fn parse_error_messages(source: &str) -> Vec<String> {
+    let (tokens, _) = lex(source);
+    let (_, errors) = parse(tokens);
+    errors.into_iter().map(|error| ErrorMessage::global().get_error_message(error).unwrap()).collect()
+}
+
+#[test]
+fn return_in_coroutine_names_the_context() {
+    let source = "coroutine c() {\n    return 1;\n}\n\nfunction main() {\n}\n";
+    assert_eq!(parse_error_messages(source), ["'return'は関数内でのみ使えます（コルーチン内では使えません） 2行 5列目"]);
+}
+
+#[test]
+fn return_at_global_scope_names_the_context() {
+    let source = "return 1;\n\nfunction main() {\n}\n";
+    assert_eq!(parse_error_messages(source), ["'return'は関数内でのみ使えます（グローバルスコープでは使えません） 1行 1列目"]);
+}
+
+#[test]
+fn variadic_parameter_must_be_last() {
+    let source = "function f(xs: int..., y: int) {\n    println(y);\n}\n";
+    let (tokens, _) = lex(source);
+    let (_, errors) = parse(tokens);
+    let positions = errors.iter()
+        .map(|error| (error.error_code.to_string(), error.row, error.col))
+        .collect::<Vec<_>>();
+    assert_eq!(positions, vec![("PARSE010".to_string(), Some(1), Some(19))]);
+}
@@ -0,0 +1,179 @@
+mod common;
+
+use common::{error_codes, error_messages, run, warning_codes};
+use green::{
+    analyzer::{semantic, tail_call::TailCall},
+    common::types::BlockType,
+    parser::node::{BlockNode, FunctionDefinitionNode, PrivateNode, RootNode},
+};
+
+#[test]
+fn word_logical_operators_accept_bools() {
+    assert_eq!(run("function main() {\n    println(true xor false);\n}\n"), "true\n");
+}
+
+#[test]
+fn word_logical_operators_reject_ints() {
+    let source = "function main() {\n    println(3 xor 5);\n}\n";
+    assert_eq!(error_codes(source), ["SEMANTIC009"]);
+    assert!(error_messages(source)[0].contains("'xor'はbool型にのみ使えます"));
+}
+
+#[test]
+fn cross_type_equality_has_targeted_message() {
+    let source = "function main() {\n    println(1 == \"1\");\n}\n";
+    assert_eq!(error_codes(source), ["SEMANTIC018"]);
+    assert_eq!(
+        error_messages(source),
+        ["異なる型の値は等しくならない: int == string\n 比較する前に明示的に型を変換してください"],
+    );
+}
+
+#[test]
+fn overloading_by_arity_is_rejected() {
+    let source = "function f(a: int) -> int {\n    return a;\n}\n\nfunction f(a: int, b: int) -> int {\n    return a + b;\n}\n\nfunction main() {\n}\n";
+    assert_eq!(error_codes(source), ["SEMANTIC021"]);
+    assert_eq!(
+        error_messages(source),
+        ["関数の重複定義: f（引数: 1個, 2個）\n 引数の個数による多重定義には対応していません"],
+    );
+}
+
+#[test]
+fn yield_in_function_is_rejected() {
+    let source = "function f() {\n    yield;\n}\n\nfunction main() {\n    f();\n}\n";
+    assert_eq!(error_messages(source), ["'yield'は'コルーチン'内でのみ使えます 2行 5列目"]);
+
+    // 構文解析を経ずに組み立てたASTは意味解析で検出する
+    let root = RootNode {
+        functions: vec![FunctionDefinitionNode {
+            name: "f".to_string(),
+            parameters: Vec::new(),
+            return_type: None,
+            block: BlockNode {
+                block_type: BlockType::Function,
+                statements: vec![PrivateNode::Yield { value: None }],
+            },
+            doc: None,
+            memoize: false,
+            is_variadic: false,
+        }],
+        coroutines: Vec::new(),
+        imports: Vec::new(),
+        todos: Vec::new(),
+    };
+    let codes = semantic::analyze(&root).errors().iter()
+        .map(|error| error.error_code.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(codes, ["SEMANTIC025"]);
+}
+
+#[test]
+fn array_literal_destructuring_length_mismatch_fails_analysis() {
+    let source = "function main() {\n    let [a, b]: int[] = [1, 2, 3];\n    println(a, b);\n}\n";
+    assert_eq!(error_codes(source), ["SEMANTIC027"]);
+}
+
+#[test]
+fn memoize_requires_return_type_and_scalar_parameters() {
+    let source = "/// @memoize\nfunction f(n: int) {\n    println(n);\n}\n\n/// @memoize\nfunction g(arr: int[]) -> int {\n    return 1;\n}\n\nfunction main() {\n    f(1);\n    println(g([1]));\n}\n";
+    assert_eq!(
+        error_messages(source),
+        [
+            "@memoizeを指定できるのは、戻り値の型とスカラー型の引数を持つ関数のみ: f",
+            "@memoizeを指定できるのは、戻り値の型とスカラー型の引数を持つ関数のみ: g",
+        ],
+    );
+}
+
+#[test]
+fn plus_between_string_and_int_is_still_an_error() {
+    assert_eq!(error_messages("function main() {\n    println(\"x\" + 5);\n}\n"), ["異なる型の演算: string + int"]);
+}
+
+#[test]
+fn arity_mismatch_shows_expected_signature() {
+    let source = "function f(a: int, name: string) -> int {\n    return a;\n}\n\nfunction main() {\n    println(f(1));\n}\n";
+    assert_eq!(
+        error_messages(source),
+        ["引数の個数の不一致（期待: 2, 取得: 1）\n 関数名: f\n シグネチャ: f(a: int, name: string) -> int\n 6行 13列目"],
+    );
+}
+
+#[test]
+fn yield_inside_coroutine_loop_is_allowed() {
+    let source = "coroutine count() -> int {\n    let i: int = 0;\n    while (i < 3) {\n        i = i + 1;\n        yield i;\n    }\n}\n\nfunction main() {\n    coro task = count();\n    println(resume task);\n}\n";
+    assert_eq!(error_codes(source), Vec::<String>::new());
+}
+
+#[test]
+fn void_call_in_arithmetic_and_comparison_fails_analysis() {
+    let nothing = "function nothing() {\n    println(\"x\");\n}\n\n";
+
+    let source = format!("{}function main() {{\n    println(1 + nothing());\n}}\n", nothing);
+    assert_eq!(error_messages(&source), ["戻り値の型が定義されていない: nothing\n 6行 17列目"]);
+
+    let source = format!("{}function main() {{\n    println(nothing() == 1);\n}}\n", nothing);
+    assert_eq!(error_messages(&source), ["戻り値の型が定義されていない: nothing\n 6行 13列目"]);
+}
+
+#[test]
+fn assert_eq_requires_same_typed_values() {
+    assert_eq!(error_codes("function main() {\n    assert_eq(1, \"a\");\n}\n"), ["SEMANTIC018"]);
+}
+
+#[test]
+fn identical_if_branches_warn() {
+    let source = "function main() {\n    let x: int = 1;\n    if (x > 0) {\n        println(x);\n    } else {\n        println( x );\n    }\n}\n";
+    assert_eq!(warning_codes(source), ["WARNING003"]);
+
+    let different = "function main() {\n    let x: int = 1;\n    if (x > 0) {\n        println(x);\n    } else {\n        println(-x);\n    }\n}\n";
+    assert_eq!(warning_codes(different), Vec::<String>::new());
+}
+
+#[test]
+fn int_variable_does_not_initialize_float_variable() {
+    let source = "function main() {\n    let n: int = 2;\n    let y: float = n;\n    println(y);\n}\n";
+    assert_eq!(error_messages(source), ["不正な変数代入: y\n float int\n 3行 9列目"]);
+}
+
+#[test]
+fn tail_calls_are_recorded_per_function() {
+    let source = r#"
+function sum_to(n: int, acc: int) -> int {
+    if (n == 0) {
+        return acc;
+    }
+    return sum_to(n - 1, acc + n);
+}
+
+function sum(n: int) -> int {
+    if (n == 0) {
+        return 0;
+    }
+    return n + sum(n - 1);
+}
+
+function start(n: int) -> int {
+    return sum_to(n, 0);
+}
+
+function main() {
+    println(sum(3), start(3));
+}
+"#;
+    let semantic = common::analyze(source).unwrap();
+    let tail_calls = |name: &str| semantic.function_table.get_function_info(name).unwrap().tail_calls;
+
+    assert_eq!(tail_calls("sum_to"), [TailCall { name: "sum_to".to_string(), is_recursive: true, row: 6, col: 12 }]);
+    assert_eq!(tail_calls("sum"), []);
+    assert_eq!(tail_calls("start"), [TailCall { name: "sum_to".to_string(), is_recursive: false, row: 17, col: 12 }]);
+    assert_eq!(tail_calls("main"), []);
+}
+
+#[test]
+fn variadic_argument_type_mismatch_is_reported() {
+    let source = "function f(xs: int...) {\n    println(xs);\n}\nfunction main() {\n    f(1, \"a\");\n}\n";
+    assert_eq!(error_codes(source), ["SEMANTIC006"]);
+    assert_eq!(error_messages(source), ["不正な変数代入: xs\n int string\n 5行 5列目"]);
+}